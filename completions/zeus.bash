@@ -40,7 +40,7 @@ _zeus() {
 
     case "${cmd}" in
         zeus)
-            opts="-h -V -d --help --version --color --debug --force --builddir --aur --rt --rtdir sync remove build query completions runtime help"
+            opts="-h -V -d --help --version --color --debug --force --builddir --aur --aurdir --archive --cache-ttl --refresh --rt --rtdir sync remove build query completions runtime help"
             if [[ ${cur} == -* || ${COMP_CWORD} -eq 1 ]] ; then
                 COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
                 return 0
@@ -58,6 +58,14 @@ _zeus() {
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
                     ;;
+                --aurdir)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --cache-ttl)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
                 --rt)
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
@@ -96,7 +104,7 @@ _zeus() {
             return 0
             ;;
         zeus__completions)
-            opts="-h --shell --help"
+            opts="-h --shell --packages --help"
             if [[ ${cur} == -* || ${COMP_CWORD} -eq 2 ]] ; then
                 COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
                 return 0
@@ -106,6 +114,10 @@ _zeus() {
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
                     ;;
+                --packages)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
                 *)
                     COMPREPLY=()
                     ;;
@@ -128,7 +140,7 @@ _zeus() {
             return 0
             ;;
         zeus__query)
-            opts="-i -h --info --by --output --help <keywords>..."
+            opts="-i -l -h --info --by --local --sort --reverse --limit --maintained-only --not-out-of-date --min-votes --date-format --output --format --select --name --help <keywords>..."
             if [[ ${cur} == -* || ${COMP_CWORD} -eq 2 ]] ; then
                 COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
                 return 0
@@ -138,8 +150,32 @@ _zeus() {
                     COMPREPLY=($(compgen -W "name description maintainer depends makedepends optdepends checkdepends" -- "${cur}"))
                     return 0
                     ;;
+                --sort)
+                    COMPREPLY=($(compgen -W "votes popularity name modified" -- "${cur}"))
+                    return 0
+                    ;;
+                --limit)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --min-votes)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --date-format)
+                    COMPREPLY=($(compgen -W "local iso" -- "${cur}"))
+                    return 0
+                    ;;
                 --output)
-                    COMPREPLY=($(compgen -W "pretty json" -- "${cur}"))
+                    COMPREPLY=($(compgen -W "pretty plain tsv json" -- "${cur}"))
+                    return 0
+                    ;;
+                --format)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --name)
+                    COMPREPLY=($(compgen -f "${cur}"))
                     return 0
                     ;;
                 *)
@@ -182,7 +218,7 @@ _zeus() {
             return 0
             ;;
         zeus__sync)
-            opts="-u -h --upgrade --install --buildargs --name --help <packages>..."
+            opts="-u -h --upgrade --install --no-review --buildargs --name --help <packages>..."
             if [[ ${cur} == -* || ${COMP_CWORD} -eq 2 ]] ; then
                 COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
                 return 0
@@ -207,3 +243,21 @@ _zeus() {
 }
 
 complete -F _zeus -o bashdefault -o default zeus
+
+_zeus_packages() {
+    _zeus "$@"
+
+    local cur="${COMP_WORDS[COMP_CWORD]}" i
+    [[ -z ${cur} || ${cur} == -* ]] && return 0
+
+    for i in "${COMP_WORDS[@]:1:COMP_CWORD-1}"; do
+        case "${i}" in
+            sync|--sync|-S*)
+                COMPREPLY+=( $(zeus completions --packages "${cur}" 2>/dev/null) )
+                return 0
+                ;;
+        esac
+    done
+}
+
+complete -F _zeus_packages -o bashdefault -o default zeus
@@ -1,12 +1,16 @@
 complete -c zeus -n "__fish_use_subcommand" -l color -d 'Colorize the output' -r -f -a "{always	,auto	,never	}"
 complete -c zeus -n "__fish_use_subcommand" -l builddir -d 'Package build directory' -r
 complete -c zeus -n "__fish_use_subcommand" -l aur -d 'AUR host' -r
+complete -c zeus -n "__fish_use_subcommand" -l aurdir -d 'Read AUR package data from a directory' -r
+complete -c zeus -n "__fish_use_subcommand" -l cache-ttl -d 'Seconds to reuse cached AUR responses for' -r
 complete -c zeus -n "__fish_use_subcommand" -l rt -d 'Specify runtime to use' -r
 complete -c zeus -n "__fish_use_subcommand" -l rtdir -d 'Specify directory to search for runtimes' -r
 complete -c zeus -n "__fish_use_subcommand" -s h -l help -d 'Print help information'
 complete -c zeus -n "__fish_use_subcommand" -s V -l version -d 'Print version information'
 complete -c zeus -n "__fish_use_subcommand" -s d -l debug -d 'Show debug logs'
 complete -c zeus -n "__fish_use_subcommand" -l force -d 'Ignore all warnings'
+complete -c zeus -n "__fish_use_subcommand" -l archive -d 'Use the AUR metadata archive instead of the RPC interface'
+complete -c zeus -n "__fish_use_subcommand" -l refresh -d 'Ignore cached AUR responses'
 complete -c zeus -n "__fish_use_subcommand" -f -a "sync" -d 'Sync packages'
 complete -c zeus -n "__fish_use_subcommand" -f -a "remove" -d 'Remove packages'
 complete -c zeus -n "__fish_use_subcommand" -f -a "build" -d 'Build/Update builder image'
@@ -18,6 +22,7 @@ complete -c zeus -n "__fish_seen_subcommand_from sync" -l buildargs -d 'Extra ar
 complete -c zeus -n "__fish_seen_subcommand_from sync" -l name -d 'Builder machine name' -r
 complete -c zeus -n "__fish_seen_subcommand_from sync" -s u -l upgrade -d 'Upgrade packages'
 complete -c zeus -n "__fish_seen_subcommand_from sync" -l install -d 'Install packages after build'
+complete -c zeus -n "__fish_seen_subcommand_from sync" -l no-review -d 'Do not review packages before building them'
 complete -c zeus -n "__fish_seen_subcommand_from sync" -s h -l help -d 'Print help information'
 complete -c zeus -n "__fish_seen_subcommand_from remove" -l name -d 'Builder machine name' -r
 complete -c zeus -n "__fish_seen_subcommand_from remove" -l uninstall -d 'Uninstall packages after remove'
@@ -26,10 +31,23 @@ complete -c zeus -n "__fish_seen_subcommand_from build" -l image -d 'Builder ima
 complete -c zeus -n "__fish_seen_subcommand_from build" -l name -d 'Builder machine name' -r
 complete -c zeus -n "__fish_seen_subcommand_from build" -s h -l help -d 'Print help information'
 complete -c zeus -n "__fish_seen_subcommand_from query" -l by -d 'Query AUR packages by' -r -f -a "{name	,description	,maintainer	,depends	,makedepends	,optdepends	,checkdepends	}"
-complete -c zeus -n "__fish_seen_subcommand_from query" -l output -d 'Output format' -r -f -a "{pretty	,json	}"
+complete -c zeus -n "__fish_seen_subcommand_from query" -l sort -d 'Sort results by' -r -f -a "{votes	,popularity	,name	,modified	}"
+complete -c zeus -n "__fish_seen_subcommand_from query" -l limit -d 'Show at most this many results' -r
+complete -c zeus -n "__fish_seen_subcommand_from query" -l min-votes -d 'Hide packages with fewer votes' -r
+complete -c zeus -n "__fish_seen_subcommand_from query" -l date-format -d 'Show dates in local time or as ISO-8601' -r -f -a "{local	,iso	}"
+complete -c zeus -n "__fish_seen_subcommand_from query" -l output -d 'Output format' -r -f -a "{pretty	,plain	,tsv	,json	}"
+complete -c zeus -n "__fish_seen_subcommand_from query" -l format -d 'Print results with a template like \'{Name} {Version}\'' -r
+complete -c zeus -n "__fish_seen_subcommand_from query" -l name -d 'Builder machine name for --select' -r
 complete -c zeus -n "__fish_seen_subcommand_from query" -s i -l info -d 'Display additional information on results'
+complete -c zeus -n "__fish_seen_subcommand_from query" -s l -l local -d 'Query synced packages'
+complete -c zeus -n "__fish_seen_subcommand_from query" -l reverse -d 'Reverse the order of results'
+complete -c zeus -n "__fish_seen_subcommand_from query" -l maintained-only -d 'Hide orphaned packages'
+complete -c zeus -n "__fish_seen_subcommand_from query" -l not-out-of-date -d 'Hide packages flagged out of date'
+complete -c zeus -n "__fish_seen_subcommand_from query" -l select -d 'Choose packages to sync and install from the results'
 complete -c zeus -n "__fish_seen_subcommand_from query" -s h -l help -d 'Print help information'
 complete -c zeus -n "__fish_seen_subcommand_from completions" -l shell -d 'Specify shell to generate completions for' -r
+complete -c zeus -n "__fish_seen_subcommand_from completions" -l packages -d 'List AUR packages starting with a prefix' -r
 complete -c zeus -n "__fish_seen_subcommand_from completions" -s h -l help -d 'Print help information'
 complete -c zeus -n "__fish_seen_subcommand_from runtime" -s l -l list -d 'List all available runtimes'
 complete -c zeus -n "__fish_seen_subcommand_from runtime" -s h -l help -d 'Print help information'
+complete -c zeus -n "__fish_seen_subcommand_from sync" -f -a "(zeus completions --packages (commandline -ct) 2>/dev/null)"
@@ -0,0 +1,200 @@
+//! The AUR metadata archive.
+//!
+//! The AUR publishes every package with its full information in
+//! `packages-meta-ext-v1.json.gz`. Downloading it once is cheaper
+//! than asking the RPC interface about hundreds of packages, so
+//! [`ArchiveTransport`] keeps a copy on disk and answers queries
+//! from it.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use const_format::formatcp;
+use flate2::read::GzDecoder;
+use reqwest::blocking::{Client, ClientBuilder};
+use serde_json::Value;
+
+use super::transport::{response, search_matches, suggestions};
+use super::{AurError, AurTransport, Package, Query};
+use crate::warning;
+
+/// Name of the archive on the AUR
+pub const ARCHIVE_NAME: &str = "packages-meta-ext-v1.json.gz";
+
+#[derive(Debug)]
+struct Archive {
+	packages: Vec<Package>,
+	by_name: HashMap<String, usize>,
+}
+
+/// Transport that answers queries from the metadata archive
+#[derive(Debug)]
+pub struct ArchiveTransport {
+	client: Client,
+	url: String,
+	path: PathBuf,
+	ttl: Duration,
+	refresh: bool,
+
+	archive: Mutex<Option<Arc<Archive>>>,
+}
+
+#[allow(dead_code)]
+impl ArchiveTransport {
+	/// Download the archive from `url` to `path` and use it for `ttl`
+	/// before downloading it again.
+	pub fn new<P: AsRef<Path>>(
+		url: &str,
+		path: P,
+		ttl: Duration,
+	) -> Self {
+		use crate::config;
+
+		Self {
+			client: ClientBuilder::new()
+				.user_agent(formatcp!(
+					"{}-{}",
+					config::NAME,
+					config::VERSION
+				))
+				.build()
+				.unwrap(),
+			url: url.to_owned(),
+			path: path.as_ref().to_owned(),
+			ttl,
+			refresh: false,
+			archive: Mutex::new(None),
+		}
+	}
+
+	/// Download the archive even if the copy on disk is fresh.
+	pub fn refresh(mut self, refresh: bool) -> Self {
+		self.refresh = refresh;
+		self
+	}
+
+	/// Check whether the copy on disk can be used as is.
+	fn is_fresh(&self) -> bool {
+		if self.refresh {
+			return false;
+		}
+
+		fs::metadata(&self.path)
+			.and_then(|x| x.modified())
+			.ok()
+			.and_then(|x| x.elapsed().ok())
+			.map(|x| x < self.ttl)
+			.unwrap_or(false)
+	}
+
+	fn download(&self) -> Result<(), AurError> {
+		let res = self.client.get(&self.url).send()?;
+
+		if !res.status().is_success() {
+			return Err(AurError::Status(res.status().as_u16()));
+		}
+
+		let tmp_path = self.path.with_extension("tmp");
+
+		fs::write(&tmp_path, res.bytes()?)?;
+		fs::rename(&tmp_path, &self.path)?;
+
+		Ok(())
+	}
+
+	fn load(&self) -> Result<Arc<Archive>, AurError> {
+		let mut archive = self.archive.lock().unwrap();
+
+		if let Some(v) = archive.as_ref() {
+			return Ok(v.clone());
+		}
+
+		if !self.is_fresh() {
+			if let Err(e) = self.download() {
+				if !self.path.exists() {
+					return Err(e);
+				}
+
+				warning!(
+					"AUR",
+					"Cannot download the metadata archive ({}), using the old one",
+					e
+				);
+			}
+		}
+
+		let packages: Vec<Package> = serde_json::from_reader(
+			BufReader::new(GzDecoder::new(File::open(&self.path)?)),
+		)?;
+
+		let by_name = packages
+			.iter()
+			.enumerate()
+			.filter_map(|(i, x)| Some((x.Name.clone()?, i)))
+			.collect();
+
+		let loaded = Arc::new(Archive { packages, by_name });
+		*archive = Some(loaded.clone());
+
+		Ok(loaded)
+	}
+}
+
+impl AurTransport for ArchiveTransport {
+	fn send(&self, query: &Query) -> Result<Value, AurError> {
+		let archive = self.load()?;
+
+		let (response_type, results): (_, Vec<Package>) = match query
+		{
+			Query::Info(names) => (
+				"multiinfo",
+				names
+					.iter()
+					.filter_map(|x| archive.by_name.get(x))
+					.map(|x| archive.packages[*x].clone())
+					.collect(),
+			),
+			Query::Search(by, keywords) => {
+				// like the RPC interface, only the last keyword counts
+				let keyword = match keywords.last() {
+					Some(v) => v.to_lowercase(),
+					None => return Ok(response("search", &[])),
+				};
+
+				(
+					"search",
+					archive
+						.packages
+						.iter()
+						.filter(|x| search_matches(x, by, &keyword))
+						.cloned()
+						.collect(),
+				)
+			},
+			Query::Suggest(prefix) => {
+				return Ok(suggestions(
+					archive
+						.packages
+						.iter()
+						.filter_map(|x| x.Name.as_deref()),
+					prefix,
+				))
+			},
+			Query::SuggestPkgbase(prefix) => {
+				return Ok(suggestions(
+					archive
+						.packages
+						.iter()
+						.filter_map(|x| x.PackageBase.as_deref()),
+					prefix,
+				))
+			},
+		};
+
+		Ok(response(response_type, &results))
+	}
+}
@@ -0,0 +1,221 @@
+//! On-disk cache of AUR responses.
+//!
+//! Every response is stored in its own file, named after a hash of
+//! the query. Fresh responses are served without asking the AUR,
+//! stale ones only when the AUR cannot be reached. Whenever a new
+//! response is stored, the ones too old to fall back on are deleted.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{AurError, AurTransport, Query, Timestamp};
+use crate::warning;
+
+/// How long stale responses are kept to fall back on, unless the TTL
+/// is even longer
+const MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// A cached response
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+	/// The query, to tell apart queries with the same hash
+	key: String,
+	/// When the response was fetched
+	fetched: Timestamp,
+	response: Value,
+}
+
+/// Transport that caches the responses of another transport
+#[derive(Debug, Clone)]
+pub struct CachedTransport {
+	inner: Arc<dyn AurTransport>,
+	dir: PathBuf,
+	ttl: Duration,
+	refresh: bool,
+}
+
+#[allow(dead_code)]
+impl CachedTransport {
+	/// Cache the responses of `inner` in `dir` for `ttl`
+	pub fn new<P: AsRef<Path>>(
+		inner: Arc<dyn AurTransport>,
+		dir: P,
+		ttl: Duration,
+	) -> Self {
+		Self {
+			inner,
+			dir: dir.as_ref().to_owned(),
+			ttl,
+			refresh: false,
+		}
+	}
+
+	/// Ignore fresh responses and always ask `inner`, the cache is
+	/// still updated and used as a fallback.
+	pub fn refresh(mut self, refresh: bool) -> Self {
+		self.refresh = refresh;
+		self
+	}
+
+	fn path(&self, key: &str) -> PathBuf {
+		self.dir
+			.join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+	}
+
+	fn load(&self, key: &str) -> Option<Entry> {
+		let entry: Entry =
+			serde_json::from_slice(&fs::read(self.path(key)).ok()?)
+				.ok()?;
+
+		match entry.key == key {
+			true => Some(entry),
+			false => None,
+		}
+	}
+
+	fn store(&self, key: &str, response: &Value) {
+		let entry = Entry {
+			key: key.to_owned(),
+			fetched: now(),
+			response: response.clone(),
+		};
+
+		let path = self.path(key);
+		let tmp_path = path.with_extension("tmp");
+
+		// the cache is only an optimization, so failing to write it
+		// is not an error
+		let _ = fs::create_dir_all(&self.dir)
+			.and_then(|_| {
+				fs::write(
+					&tmp_path,
+					serde_json::to_vec(&entry).unwrap_or_default(),
+				)
+			})
+			.and_then(|_| fs::rename(&tmp_path, &path));
+
+		self.prune(&path);
+	}
+
+	/// Delete the entries too old to fall back on, except `keep`.
+	fn prune(&self, keep: &Path) {
+		let max_age = self.ttl.max(MAX_AGE);
+
+		let entries = match fs::read_dir(&self.dir) {
+			Ok(v) => v,
+			Err(_) => return,
+		};
+
+		for entry in entries.flatten() {
+			let path = entry.path();
+
+			if path == keep
+				|| path.extension().and_then(|x| x.to_str())
+					!= Some("json")
+			{
+				continue;
+			}
+
+			let expired = entry
+				.metadata()
+				.and_then(|x| x.modified())
+				.ok()
+				.and_then(|x| x.elapsed().ok())
+				.map(|x| x > max_age)
+				.unwrap_or(false);
+
+			if expired {
+				let _ = fs::remove_file(&path);
+			}
+		}
+	}
+}
+
+impl AurTransport for CachedTransport {
+	fn send(&self, query: &Query) -> Result<Value, AurError> {
+		let key = cache_key(query);
+		let cached = self.load(&key);
+
+		if let Some(entry) = &cached {
+			let age = now().saturating_sub(entry.fetched);
+
+			if !self.refresh && age < self.ttl.as_secs() {
+				return Ok(entry.response.clone());
+			}
+		}
+
+		match self.inner.send(query) {
+			Ok(v) => {
+				// never cache errors
+				if v.get("error").is_none() {
+					self.store(&key, &v);
+				}
+
+				Ok(v)
+			},
+			Err(e @ AurError::Http(_))
+			| Err(e @ AurError::Status(500..)) => match cached {
+				Some(entry) => {
+					let age = now().saturating_sub(entry.fetched);
+
+					warning!(
+						"AUR",
+						"Cannot reach the AUR ({}), using data from {} s ago",
+						e,
+						age
+					);
+					Ok(entry.response)
+				},
+				None => Err(e),
+			},
+			Err(e) => Err(e),
+		}
+	}
+}
+
+fn now() -> Timestamp {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|x| x.as_secs())
+		.unwrap_or(0)
+}
+
+/// A string that identifies `query`
+fn cache_key(query: &Query) -> String {
+	let (kind, args) = match query {
+		Query::Info(args) => ("info".to_owned(), args),
+		Query::Search(by, args) => (format!("search-{}", by), args),
+		Query::Suggest(prefix) => {
+			return format!("suggest\n{}", prefix)
+		},
+		Query::SuggestPkgbase(prefix) => {
+			return format!("suggest-pkgbase\n{}", prefix)
+		},
+	};
+
+	let mut key = kind;
+	for arg in args {
+		key.push('\n');
+		key.push_str(arg);
+	}
+
+	key
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is stable across
+/// releases
+fn fnv1a(data: &[u8]) -> u64 {
+	let mut hash: u64 = 0xcbf29ce484222325;
+
+	for byte in data {
+		hash ^= *byte as u64;
+		hash = hash.wrapping_mul(0x100000001b3);
+	}
+
+	hash
+}
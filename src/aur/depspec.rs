@@ -0,0 +1,197 @@
+//! Dependency specifiers.
+//!
+//! Entries of `Depends`, `Provides`, `Conflicts` and friends are
+//! strings like `python>=3.10` or `libfoo.so=1-64`. The rules for
+//! matching them follow libalpm's `alpm_depcmp`.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::{Package, Version};
+
+/// Version constraint of a dependency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepOp {
+	/// `<`
+	Lt,
+	/// `<=`
+	Le,
+	/// `=`
+	Eq,
+	/// `>=`
+	Ge,
+	/// `>`
+	Gt,
+}
+
+/// A parsed dependency like `name>=version`
+///
+/// # Example:
+/// ```
+/// use zeus::aur::{DepOp, DepSpec, Version};
+///
+/// let spec = DepSpec::from("python>=3.10");
+///
+/// assert_eq!(spec.name, "python");
+/// assert_eq!(spec.op, Some(DepOp::Ge));
+/// assert!(spec.satisfied_by("python", Some(&Version::from("3.11.1-1"))));
+/// assert!(!spec.satisfied_by("python", Some(&Version::from("3.9-1"))));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepSpec {
+	pub name: String,
+	pub op: Option<DepOp>,
+	pub version: Option<Version>,
+}
+
+impl DepOp {
+	/// Check whether `ordering`, the result of comparing a version
+	/// with the one of the constraint, satisfies the constraint.
+	pub fn matches(&self, ordering: Ordering) -> bool {
+		match self {
+			DepOp::Lt => ordering == Ordering::Less,
+			DepOp::Le => ordering != Ordering::Greater,
+			DepOp::Eq => ordering == Ordering::Equal,
+			DepOp::Ge => ordering != Ordering::Less,
+			DepOp::Gt => ordering == Ordering::Greater,
+		}
+	}
+}
+
+impl fmt::Display for DepOp {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				DepOp::Lt => "<",
+				DepOp::Le => "<=",
+				DepOp::Eq => "=",
+				DepOp::Ge => ">=",
+				DepOp::Gt => ">",
+			}
+		)
+	}
+}
+
+impl From<&str> for DepSpec {
+	fn from(s: &str) -> Self {
+		// optional dependencies carry a description after ": "
+		let s = match s.find(": ") {
+			Some(i) => &s[..i],
+			None => s,
+		}
+		.trim();
+
+		let i = match s.find(['<', '>', '=']) {
+			Some(v) => v,
+			None => {
+				return Self {
+					name: s.to_owned(),
+					op: None,
+					version: None,
+				}
+			},
+		};
+
+		let (op, len) = match &s[i..] {
+			x if x.starts_with("<=") => (DepOp::Le, 2),
+			x if x.starts_with(">=") => (DepOp::Ge, 2),
+			x if x.starts_with('<') => (DepOp::Lt, 1),
+			x if x.starts_with('>') => (DepOp::Gt, 1),
+			_ => (DepOp::Eq, 1),
+		};
+
+		Self {
+			name: s[..i].to_owned(),
+			op: Some(op),
+			version: Some(Version::from(&s[i + len..])),
+		}
+	}
+}
+
+impl From<&String> for DepSpec {
+	fn from(s: &String) -> Self {
+		Self::from(s.as_str())
+	}
+}
+
+impl fmt::Display for DepSpec {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match (&self.op, &self.version) {
+			(Some(op), Some(version)) => {
+				write!(f, "{}{}{}", self.name, op, version)
+			},
+			_ => write!(f, "{}", self.name),
+		}
+	}
+}
+
+#[allow(dead_code)]
+impl DepSpec {
+	/// Check whether the version constraint accepts `version`.
+	///
+	/// A spec without a constraint accepts anything, a spec with one
+	/// never accepts an unknown version.
+	pub fn accepts(&self, version: Option<&Version>) -> bool {
+		match (&self.op, &self.version, version) {
+			(None, _, _) | (_, None, _) => true,
+			(Some(op), Some(wanted), Some(version)) => {
+				op.matches(version.vercmp(wanted))
+			},
+			(Some(_), Some(_), None) => false,
+		}
+	}
+
+	/// Check whether a package called `name` at `version` satisfies
+	/// this spec.
+	pub fn satisfied_by(
+		&self,
+		name: &str,
+		version: Option<&Version>,
+	) -> bool {
+		self.name == name && self.accepts(version)
+	}
+
+	/// Check whether a `Provides` entry like `libfoo.so=1-64`
+	/// satisfies this spec.
+	///
+	/// An unversioned provision cannot satisfy a versioned spec.
+	pub fn satisfied_by_provision(
+		&self,
+		provision: &DepSpec,
+	) -> bool {
+		self.satisfied_by(&provision.name, provision.version.as_ref())
+	}
+}
+
+#[allow(dead_code)]
+impl Package {
+	/// Check whether this package satisfies `spec`, either by its
+	/// own name and version or by one of its `Provides`.
+	pub fn satisfies(&self, spec: &DepSpec) -> bool {
+		if let Some(name) = &self.Name {
+			let version = self.Version.as_deref().map(Version::from);
+
+			if spec.satisfied_by(name, version.as_ref()) {
+				return true;
+			}
+		}
+
+		self.Provides
+			.iter()
+			.flatten()
+			.any(|x| spec.satisfied_by_provision(&DepSpec::from(x)))
+	}
+
+	/// All dependencies needed to build and install this package.
+	pub fn build_dependencies(
+		&self,
+	) -> impl Iterator<Item = DepSpec> + '_ {
+		[&self.Depends, &self.MakeDepends, &self.CheckDepends]
+			.into_iter()
+			.flatten()
+			.flatten()
+			.map(DepSpec::from)
+	}
+}
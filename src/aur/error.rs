@@ -0,0 +1,101 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Errors that can happen while querying the AUR
+#[derive(Debug)]
+pub enum AurError {
+	/// The request could not be sent or the response read
+	Http(reqwest::Error),
+	/// A local data source could not be read
+	Io(io::Error),
+	/// The response was not valid
+	Json(serde_json::Error),
+	/// The server answered with an unexpected HTTP status
+	Status(u16),
+	/// Too many requests were made, with the seconds to wait before
+	/// trying again if the server said so
+	RateLimited(Option<u64>),
+	/// The query matched more packages than the AUR returns
+	TooManyResults,
+	/// The AUR rejected the query
+	Rpc(String),
+	/// A `.SRCINFO` is not valid, with the line of the problem
+	SrcInfo(usize, String),
+}
+
+impl AurError {
+	/// Map the `error` of an RPC response to an error.
+	pub(super) fn from_rpc(message: &str) -> Self {
+		match message {
+			"Too many package results." => Self::TooManyResults,
+			_ => Self::Rpc(message.to_owned()),
+		}
+	}
+}
+
+impl fmt::Display for AurError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			AurError::Http(e) => {
+				write!(f, "cannot reach the AUR: {}", e)
+			},
+			AurError::Io(e) => {
+				write!(f, "cannot read AUR data: {}", e)
+			},
+			AurError::Json(e) => write!(f, "invalid response: {}", e),
+			AurError::Status(code) => {
+				write!(f, "the AUR responded with HTTP {}", code)
+			},
+			AurError::RateLimited(Some(secs)) => {
+				write!(f, "rate limited, retry after {} s", secs)
+			},
+			AurError::RateLimited(None) => {
+				write!(f, "rate limited, retry later")
+			},
+			AurError::TooManyResults => write!(
+				f,
+				"too many packages found, narrow your search"
+			),
+			AurError::Rpc(message) => {
+				write!(f, "the AUR rejected the query: {}", message)
+			},
+			AurError::SrcInfo(line, message) => {
+				write!(
+					f,
+					"invalid .SRCINFO at line {}: {}",
+					line, message
+				)
+			},
+		}
+	}
+}
+
+impl Error for AurError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			AurError::Http(e) => Some(e),
+			AurError::Io(e) => Some(e),
+			AurError::Json(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+impl From<reqwest::Error> for AurError {
+	fn from(e: reqwest::Error) -> Self {
+		Self::Http(e)
+	}
+}
+
+impl From<io::Error> for AurError {
+	fn from(e: io::Error) -> Self {
+		Self::Io(e)
+	}
+}
+
+impl From<serde_json::Error> for AurError {
+	fn from(e: serde_json::Error) -> Self {
+		Self::Json(e)
+	}
+}
@@ -0,0 +1,335 @@
+//! An in-process mock of the AUR RPC interface.
+//!
+//! The server answers `info`, `search` and `suggest` queries of RPC
+//! v5 and serves the metadata archive from a fixed set of packages,
+//! so code that talks to the AUR can be tested without network
+//! access.
+//!
+//! # Example:
+//! ```
+//! use zeus::aur::{mock::MockServer, Package};
+//!
+//! let mut package = Package::default();
+//! package.Name = Some("zeus".to_owned());
+//!
+//! let server = MockServer::new(vec![package]).unwrap();
+//!
+//! let res = server.aur().info(&["zeus"]).unwrap();
+//! assert_eq!(res.resultcount, 1);
+//! ```
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{
+	AtomicBool, AtomicU64, AtomicUsize, Ordering,
+};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use reqwest::Url;
+use serde_json::json;
+
+use super::transport::{search_matches, suggestions};
+use super::{Aur, By, Package, ARCHIVE_NAME};
+
+#[derive(Debug)]
+struct State {
+	packages: Vec<Package>,
+	requests: Mutex<Vec<String>>,
+	shutdown: AtomicBool,
+
+	max_results: AtomicUsize,
+	retry_after: AtomicU64,
+}
+
+/// A mock AUR server listening on localhost
+#[derive(Debug)]
+pub struct MockServer {
+	addr: SocketAddr,
+	state: Arc<State>,
+	thread: Option<JoinHandle<()>>,
+}
+
+#[allow(dead_code)]
+impl MockServer {
+	/// Start a server that knows about `packages`.
+	pub fn new(packages: Vec<Package>) -> io::Result<Self> {
+		let listener = TcpListener::bind("127.0.0.1:0")?;
+		let addr = listener.local_addr()?;
+
+		let state = Arc::new(State {
+			packages,
+			requests: Mutex::new(vec![]),
+			shutdown: AtomicBool::new(false),
+			max_results: AtomicUsize::new(5000),
+			retry_after: AtomicU64::new(0),
+		});
+
+		let thread_state = state.clone();
+		let thread = thread::spawn(move || {
+			for stream in listener.incoming() {
+				if thread_state.shutdown.load(Ordering::SeqCst) {
+					break;
+				}
+
+				if let Ok(stream) = stream {
+					let _ = handle_connection(&thread_state, stream);
+				}
+			}
+		});
+
+		Ok(Self { addr, state, thread: Some(thread) })
+	}
+
+	/// Address the server is listening on
+	pub fn addr(&self) -> SocketAddr {
+		self.addr
+	}
+
+	/// Get an `Aur` instance that talks to this server.
+	pub fn aur(&self) -> Aur {
+		Aur::new()
+			.protocol("http".to_owned())
+			.host(self.addr.to_string())
+			.build()
+	}
+
+	/// Request targets of every request received so far, like
+	/// `/rpc/?v=5&type=info&arg%5B%5D=zeus`
+	pub fn requests(&self) -> Vec<String> {
+		self.state.requests.lock().unwrap().clone()
+	}
+
+	/// Answer searches with more than `max` results with an error,
+	/// like the AUR does. The default is 5000.
+	pub fn max_results(&self, max: usize) {
+		self.state.max_results.store(max, Ordering::SeqCst);
+	}
+
+	/// Answer every following request with `429 Too Many Requests`
+	/// and a `Retry-After` of `secs`, or stop doing so if `secs` is 0.
+	pub fn rate_limit(&self, secs: u64) {
+		self.state.retry_after.store(secs, Ordering::SeqCst);
+	}
+}
+
+impl Drop for MockServer {
+	fn drop(&mut self) {
+		self.state.shutdown.store(true, Ordering::SeqCst);
+
+		// wake up the server thread so it sees the flag
+		let _ = TcpStream::connect(self.addr);
+
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
+
+fn handle_connection(
+	state: &State,
+	stream: TcpStream,
+) -> io::Result<()> {
+	let mut reader = BufReader::new(&stream);
+
+	let mut request_line = String::new();
+	reader.read_line(&mut request_line)?;
+
+	// skip the headers, GET requests have no body
+	loop {
+		let mut line = String::new();
+		if reader.read_line(&mut line)? == 0 || line.trim().is_empty()
+		{
+			break;
+		}
+	}
+
+	let target = request_line
+		.split_whitespace()
+		.nth(1)
+		.unwrap_or("/")
+		.to_owned();
+
+	state.requests.lock().unwrap().push(target.clone());
+
+	let retry_after = state.retry_after.load(Ordering::SeqCst);
+
+	let (status, body) = match Url::parse("http://localhost")
+		.and_then(|x| x.join(&target))
+	{
+		_ if retry_after != 0 => (
+			"429 Too Many Requests",
+			error_response("Rate limit reached").into_bytes(),
+		),
+		Ok(url) if url.path().trim_end_matches('/') == "/rpc" => {
+			("200 OK", answer(state, &url).into_bytes())
+		},
+		Ok(url) if url.path() == format!("/{}", ARCHIVE_NAME) => {
+			("200 OK", archive(state)?)
+		},
+		_ => ("404 Not Found", json!({}).to_string().into_bytes()),
+	};
+
+	let mut headers = String::new();
+	if retry_after != 0 {
+		headers
+			.push_str(&format!("Retry-After: {}\r\n", retry_after));
+	}
+
+	let mut stream = &stream;
+	write!(
+		stream,
+		"HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+		status,
+		body.len(),
+		headers,
+	)?;
+	stream.write_all(&body)?;
+	stream.flush()
+}
+
+/// The metadata archive with every package
+fn archive(state: &State) -> io::Result<Vec<u8>> {
+	let mut encoder = GzEncoder::new(vec![], Compression::default());
+	serde_json::to_writer(&mut encoder, &state.packages)?;
+	encoder.finish()
+}
+
+fn error_response(message: &str) -> String {
+	json!({
+		"error": message,
+		"resultcount": 0,
+		"results": [],
+		"type": "error",
+		"version": 5,
+	})
+	.to_string()
+}
+
+fn answer(state: &State, url: &Url) -> String {
+	let mut query_type = None;
+	let mut by = "name-desc".to_owned();
+	let mut args: Vec<String> = vec![];
+
+	for (key, value) in url.query_pairs() {
+		match key.as_ref() {
+			"type" => query_type = Some(value.into_owned()),
+			"by" => by = value.into_owned(),
+			"arg" | "arg[]" => args.push(value.into_owned()),
+			_ => {},
+		}
+	}
+
+	let (response_type, results): (_, Vec<&Package>) =
+		match query_type.as_deref() {
+			Some("info") | Some("multiinfo") => (
+				"multiinfo",
+				state
+					.packages
+					.iter()
+					.filter(|x| {
+						args.iter()
+							.any(|a| x.Name.as_ref() == Some(a))
+					})
+					.collect(),
+			),
+			Some("search") => {
+				let arg = match args.last() {
+					Some(v) if v.len() >= 2 => v.to_lowercase(),
+					_ => {
+						return error_response("Query arg too small.")
+					},
+				};
+
+				let by = match by.as_str() {
+					"name" => By::Name,
+					"name-desc" => By::NameDesc,
+					"maintainer" => By::Maintainer,
+					"depends" => By::Depends,
+					"makedepends" => By::MakeDepends,
+					"optdepends" => By::OptDepends,
+					"checkdepends" => By::CheckDepends,
+					_ => {
+						return error_response(
+							"Incorrect by field specified.",
+						)
+					},
+				};
+
+				(
+					"search",
+					state
+						.packages
+						.iter()
+						.filter(|x| search_matches(x, &by, &arg))
+						.collect(),
+				)
+			},
+			Some("suggest") => {
+				return suggestions(
+					state
+						.packages
+						.iter()
+						.filter_map(|x| x.Name.as_deref()),
+					args.last()
+						.map(|x| x.as_str())
+						.unwrap_or_default(),
+				)
+				.to_string()
+			},
+			Some("suggest-pkgbase") => {
+				return suggestions(
+					state
+						.packages
+						.iter()
+						.filter_map(|x| x.PackageBase.as_deref()),
+					args.last()
+						.map(|x| x.as_str())
+						.unwrap_or_default(),
+				)
+				.to_string()
+			},
+			_ => {
+				return error_response(
+					"Incorrect request type specified.",
+				)
+			},
+		};
+
+	if response_type == "search"
+		&& results.len() > state.max_results.load(Ordering::SeqCst)
+	{
+		return error_response("Too many package results.");
+	}
+
+	let results: Vec<Package> = results
+		.into_iter()
+		.cloned()
+		.map(|mut x| {
+			// search results only carry the basic package info
+			if response_type == "search" {
+				x.Depends = None;
+				x.MakeDepends = None;
+				x.OptDepends = None;
+				x.CheckDepends = None;
+				x.Conflicts = None;
+				x.Provides = None;
+				x.Replaces = None;
+				x.Groups = None;
+				x.License = None;
+				x.Keywords = None;
+			}
+			x
+		})
+		.collect();
+
+	json!({
+		"resultcount": results.len(),
+		"results": results,
+		"type": response_type,
+		"version": 5,
+	})
+	.to_string()
+}
@@ -0,0 +1,669 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+mod archive;
+mod cache;
+mod depspec;
+mod error;
+pub mod mock;
+mod split;
+mod srcinfo;
+pub mod transport;
+mod version;
+#[allow(unused_imports)]
+pub use archive::{ArchiveTransport, ARCHIVE_NAME};
+#[allow(unused_imports)]
+pub use cache::CachedTransport;
+#[allow(unused_imports)]
+pub use depspec::{DepOp, DepSpec};
+pub use error::AurError;
+#[allow(unused_imports)]
+pub use split::{group_by_base, package_file_name};
+#[allow(unused_imports)]
+pub use srcinfo::SrcInfo;
+#[allow(unused_imports)]
+pub use transport::{
+	AurTransport, FileTransport, HttpTransport, Query,
+};
+#[allow(unused_imports)]
+pub use version::{vercmp, Version};
+
+/// Type alias for timestamps
+pub type Timestamp = u64;
+/// Type alias for id fields
+pub type Id = u64;
+/// Type alias for RPC version number fields
+pub type RpcVersion = u8;
+
+/// Longest request URL the AUR accepts
+pub const MAX_URL_LEN: usize = 4443;
+
+/// Type alias for request results
+pub type AurResult = Result<AurResponse, AurError>;
+
+/// Package search types
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum By {
+	/// Search by package name
+	Name,
+	/// Search by package name and description
+	NameDesc,
+	/// Search by maintainer
+	Maintainer,
+	/// Search by dependencies
+	Depends,
+	/// Search by dev dependencies
+	MakeDepends,
+	/// Search by optional dependencies
+	OptDepends,
+	/// Search by testing dependencies
+	CheckDepends,
+}
+
+#[derive(Debug)]
+pub struct AurBuilder {
+	host: String,
+	protocol: String,
+
+	version: RpcVersion,
+	rpc_path: String,
+
+	max_url_len: usize,
+	jobs: usize,
+
+	transport: Option<Arc<dyn AurTransport>>,
+
+	cache_dir: Option<PathBuf>,
+	cache_ttl: Duration,
+	archive_path: Option<PathBuf>,
+	archive_ttl: Duration,
+	refresh: bool,
+}
+
+/// The transport of an `Aur`, which is not part of the config sent
+/// to the builder
+#[derive(Debug, Default, Clone)]
+struct Transport(Option<Arc<dyn AurTransport>>);
+
+impl PartialEq for Transport {
+	fn eq(&self, other: &Self) -> bool {
+		match (&self.0, &other.0) {
+			(Some(a), Some(b)) => Arc::ptr_eq(a, b),
+			(None, None) => true,
+			_ => false,
+		}
+	}
+}
+
+/// Structure representing an AUR instance
+#[derive(
+	Debug, Default, PartialEq, Clone, Serialize, Deserialize,
+)]
+pub struct Aur {
+	base_url: String,
+	rpc_url: String,
+
+	max_url_len: usize,
+	jobs: usize,
+
+	#[serde(skip)]
+	transport: Transport,
+}
+
+#[allow(non_snake_case)]
+#[derive(
+	Debug, Default, PartialEq, Clone, Serialize, Deserialize,
+)]
+pub struct Package {
+	pub ID: Option<Id>,
+	pub Name: Option<String>,
+	pub PackageBaseID: Option<Id>,
+	pub PackageBase: Option<String>,
+	pub Version: Option<String>,
+	pub Description: Option<String>,
+	pub URL: Option<String>,
+	pub NumVotes: Option<u64>,
+	pub Popularity: Option<f32>,
+	pub OutOfDate: Option<Timestamp>,
+	pub Maintainer: Option<String>,
+	pub FirstSubmitted: Option<Timestamp>,
+	pub LastModified: Option<Timestamp>,
+	pub URLPath: Option<String>,
+
+	pub Depends: Option<Vec<String>>,
+	pub MakeDepends: Option<Vec<String>>,
+	pub OptDepends: Option<Vec<String>>,
+	pub CheckDepends: Option<Vec<String>>,
+	pub Conflicts: Option<Vec<String>>,
+	pub Provides: Option<Vec<String>>,
+	pub Replaces: Option<Vec<String>>,
+	pub Groups: Option<Vec<String>>,
+	pub License: Option<Vec<String>>,
+	pub Keywords: Option<Vec<String>>,
+}
+
+/// Structure representing the responses
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AurResponse {
+	/// Number of returned packages
+	#[serde(default)]
+	pub resultcount: usize,
+
+	/// Packages returned
+	#[serde(default)]
+	pub results: Vec<Package>,
+
+	/// Error message, only present in responses of type `error`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+
+	/// Query type
+	pub r#type: String,
+	/// AUR version
+	pub version: RpcVersion,
+}
+
+#[allow(dead_code)]
+impl Package {
+	/// Name of the package base, which the git repository and the
+	/// build directory are named after. Packages that are not split
+	/// are their own base.
+	pub fn base(&self) -> Option<&str> {
+		self.PackageBase.as_deref().or(self.Name.as_deref())
+	}
+}
+
+impl fmt::Display for By {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				By::Name => "name".to_owned(),
+				By::NameDesc => "name-desc".to_owned(),
+				By::Maintainer => "maintainer".to_owned(),
+				By::Depends => "depends".to_owned(),
+				By::MakeDepends => "makedepends".to_owned(),
+				By::OptDepends => "optdepends".to_owned(),
+				By::CheckDepends => "checkdepends".to_owned(),
+			}
+		)
+	}
+}
+
+impl FromStr for By {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"name" => Ok(Self::Name),
+			"description" => Ok(Self::NameDesc),
+			"maintainer" => Ok(Self::Maintainer),
+			"depends" => Ok(Self::Depends),
+			"makedepends" => Ok(Self::MakeDepends),
+			"optdepends" => Ok(Self::OptDepends),
+			"checkdepends" => Ok(Self::CheckDepends),
+			_ => unreachable!(),
+		}
+	}
+}
+
+#[allow(dead_code)]
+impl AurBuilder {
+	/// Create a new AUR instance
+	pub fn build(self) -> Aur {
+		let rpc_url = format!(
+			"{}://{}/{}/?v={}",
+			self.protocol, self.host, self.rpc_path, self.version
+		);
+
+		let base_url = format!("{}://{}/", self.protocol, self.host);
+
+		let mut transport = match (self.transport, self.archive_path)
+		{
+			(Some(v), _) => v,
+			(None, Some(path)) => Arc::new(
+				ArchiveTransport::new(
+					&format!("{}{}", base_url, ARCHIVE_NAME),
+					path,
+					self.archive_ttl,
+				)
+				.refresh(self.refresh),
+			),
+			(None, None) => Arc::new(HttpTransport::new(&rpc_url)),
+		};
+
+		if let Some(dir) = self.cache_dir {
+			transport = Arc::new(
+				CachedTransport::new(transport, dir, self.cache_ttl)
+					.refresh(self.refresh),
+			);
+		}
+
+		Aur {
+			base_url,
+			rpc_url,
+			max_url_len: self.max_url_len,
+			jobs: self.jobs,
+			transport: Transport(Some(transport)),
+		}
+	}
+
+	/// Set AUR host
+	///
+	/// # Example:
+	/// ```
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().host("aur.example.com".to_owned()).build();
+	/// ```
+	pub fn host(mut self, host: String) -> Self {
+		self.host = host;
+		self
+	}
+
+	/// Set AUR protocol
+	///
+	/// # Example:
+	/// ```
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().protocol("https".to_owned()).build();
+	/// ```
+	pub fn protocol(mut self, protocol: String) -> Self {
+		self.protocol = protocol;
+		self
+	}
+
+	/// Set AUR RPC version
+	///
+	/// # Example:
+	/// ```
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().version(5).build();
+	/// ```
+	pub fn version(mut self, version: u8) -> Self {
+		self.version = version;
+		self
+	}
+
+	/// Set AUR RPC endpoint path from /
+	///
+	/// # Example:
+	/// ```
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().rpc_path("rpc/".to_owned()).build();
+	/// ```
+	pub fn rpc_path(mut self, rpc_path: String) -> Self {
+		self.rpc_path = rpc_path;
+		self
+	}
+
+	/// Set the maximum length of request URLs, longer `info`
+	/// requests are split into several
+	///
+	/// # Example:
+	/// ```
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().max_url_len(2048).build();
+	/// ```
+	pub fn max_url_len(mut self, max_url_len: usize) -> Self {
+		self.max_url_len = max_url_len;
+		self
+	}
+
+	/// Set how many requests of a split query can run at once
+	///
+	/// # Example:
+	/// ```
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().jobs(1).build();
+	/// ```
+	pub fn jobs(mut self, jobs: usize) -> Self {
+		self.jobs = jobs;
+		self
+	}
+
+	/// Set the transport used for queries, by default queries go to
+	/// the RPC interface over HTTP
+	///
+	/// # Example:
+	/// ```
+	/// use zeus::aur::{Aur, FileTransport};
+	///
+	/// let transport = FileTransport::new("/srv/aur");
+	///
+	/// let aur_instance = Aur::new().transport(transport).build();
+	/// ```
+	pub fn transport<T>(mut self, transport: T) -> Self
+	where
+		T: AurTransport + 'static,
+	{
+		self.transport = Some(Arc::new(transport));
+		self
+	}
+
+	/// Cache responses in `dir` and reuse them for `ttl`
+	///
+	/// # Example:
+	/// ```
+	/// use std::time::Duration;
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new()
+	///     .cache("/var/cache/aur/.zeus.cache", Duration::from_secs(600))
+	///     .build();
+	/// ```
+	pub fn cache<P: AsRef<Path>>(
+		mut self,
+		dir: P,
+		ttl: Duration,
+	) -> Self {
+		self.cache_dir = Some(dir.as_ref().to_owned());
+		self.cache_ttl = ttl;
+		self
+	}
+
+	/// Answer queries from the metadata archive, which is downloaded
+	/// to `path` and reused for `ttl`
+	///
+	/// # Example:
+	/// ```
+	/// use std::time::Duration;
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new()
+	///     .archive("/var/cache/aur/.zeus.meta.json.gz", Duration::from_secs(600))
+	///     .build();
+	/// ```
+	pub fn archive<P: AsRef<Path>>(
+		mut self,
+		path: P,
+		ttl: Duration,
+	) -> Self {
+		self.archive_path = Some(path.as_ref().to_owned());
+		self.archive_ttl = ttl;
+		self
+	}
+
+	/// Always ask the AUR, even if there is a fresh cached response
+	///
+	/// # Example:
+	/// ```
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().refresh(true).build();
+	/// ```
+	pub fn refresh(mut self, refresh: bool) -> Self {
+		self.refresh = refresh;
+		self
+	}
+}
+
+#[allow(dead_code)]
+impl Aur {
+	/// Create a new AurBuilder
+	pub fn new() -> AurBuilder {
+		AurBuilder {
+			host: "aur.archlinux.org".to_owned(),
+			protocol: "https".to_owned(),
+			rpc_path: "rpc".to_owned(),
+			version: 5,
+			max_url_len: MAX_URL_LEN,
+			jobs: 4,
+			transport: None,
+			cache_dir: None,
+			cache_ttl: Duration::ZERO,
+			archive_path: None,
+			archive_ttl: Duration::ZERO,
+			refresh: false,
+		}
+	}
+
+	/// Get full URL of AUR instance
+	///
+	/// # Example:
+	/// ```
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().build();
+	///
+	/// assert_eq!(aur_instance.get_url(), "https://aur.archlinux.org/");
+	/// ```
+	pub fn get_url(&self) -> &str {
+		&self.base_url
+	}
+
+	/// Get full URL of AUR RPC endpoint
+	///
+	/// # Example:
+	/// ```
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().build();
+	///
+	/// let url = aur_instance.get_rpc_url();
+	/// ```
+	pub fn get_rpc_url(&self) -> &str {
+		&self.rpc_url
+	}
+
+	/// Search for packages.
+	///
+	/// Packages must match every keyword.
+	///
+	/// # Example:
+	/// ```no_run
+	/// use zeus::aur::{Aur, By};
+	///
+	/// let aur_instance = Aur::new().build();
+	///
+	/// let keywords = vec!["zeus"];
+	///
+	/// let response = aur_instance.search(By::Name, &keywords);
+	/// ```
+	pub fn search<T>(&self, by: By, keywords: &[T]) -> AurResult
+	where
+		T: fmt::Display,
+	{
+		let mut unique: Vec<String> = vec![];
+		for keyword in keywords.iter().map(|x| x.to_string()) {
+			if !unique.contains(&keyword) {
+				unique.push(keyword);
+			}
+		}
+		let keywords = unique;
+
+		if keywords.len() <= 1 {
+			return self.send(&Query::Search(by, keywords));
+		}
+
+		// the RPC interface only looks at one keyword per request, so
+		// every keyword gets its own and only the packages found by
+		// all of them are kept
+		let queries: Vec<Query> = keywords
+			.iter()
+			.map(|x| Query::Search(by.clone(), vec![x.clone()]))
+			.collect();
+
+		let mut res = self.send_all(&queries)?;
+
+		let mut found: HashMap<Option<String>, usize> =
+			HashMap::new();
+		for package in &res.results {
+			*found.entry(package.Name.clone()).or_default() += 1;
+		}
+
+		res.results.retain(|x| {
+			match found.get_mut(&x.Name) {
+				// keep the first of each
+				Some(count) if *count == keywords.len() => {
+					*count = 0;
+					true
+				},
+				_ => false,
+			}
+		});
+		res.resultcount = res.results.len();
+
+		Ok(res)
+	}
+
+	/// Request package information.
+	///
+	/// Example:
+	/// ```no_run
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().build();
+	///
+	/// let packages = vec!["zeus", "zeus-bin"];
+	///
+	/// let response = aur_instance.info(&packages);
+	/// ```
+	pub fn info<T>(&self, packages: &[T]) -> AurResult
+	where
+		T: fmt::Display,
+	{
+		let base_len = self.rpc_url.len() + "&type=info".len();
+
+		let mut queries = vec![];
+		let mut chunk: Vec<String> = vec![];
+		let mut len = base_len;
+
+		for package in packages {
+			let package = package.to_string();
+			let arg_len = "&arg%5B%5D=".len()
+				+ transport::encoded_len(&package);
+
+			if !chunk.is_empty() && len + arg_len > self.max_url_len {
+				queries.push(Query::Info(chunk));
+				chunk = vec![];
+				len = base_len;
+			}
+
+			len += arg_len;
+			chunk.push(package);
+		}
+
+		if !chunk.is_empty() || queries.is_empty() {
+			queries.push(Query::Info(chunk));
+		}
+
+		self.send_all(&queries)
+	}
+
+	/// Send several queries, `jobs` at a time, and merge their
+	/// responses in order.
+	fn send_all(&self, queries: &[Query]) -> AurResult {
+		let mut responses = vec![];
+
+		for batch in queries.chunks(self.jobs.max(1)) {
+			if batch.len() == 1 {
+				responses.push(self.send(&batch[0]));
+				continue;
+			}
+
+			thread::scope(|s| {
+				let handles: Vec<_> = batch
+					.iter()
+					.map(|x| s.spawn(move || self.send(x)))
+					.collect();
+
+				for handle in handles {
+					responses.push(handle.join().unwrap());
+				}
+			});
+		}
+
+		let mut responses = responses.into_iter();
+
+		// there is always at least one query
+		let mut res = responses.next().unwrap()?;
+		for other in responses {
+			let other = other?;
+
+			res.resultcount += other.resultcount;
+			res.results.extend(other.results);
+		}
+
+		Ok(res)
+	}
+
+	/// Get names of packages starting with `prefix`, at most 20.
+	///
+	/// Example:
+	/// ```no_run
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().build();
+	///
+	/// let names = aur_instance.suggest("zeu");
+	/// ```
+	pub fn suggest(
+		&self,
+		prefix: &str,
+	) -> Result<Vec<String>, AurError> {
+		let res =
+			self.send_raw(&Query::Suggest(prefix.to_owned()))?;
+		Ok(serde_json::from_value(res)?)
+	}
+
+	/// Get names of package bases starting with `prefix`, at most 20.
+	///
+	/// Example:
+	/// ```no_run
+	/// use zeus::aur::Aur;
+	///
+	/// let aur_instance = Aur::new().build();
+	///
+	/// let names = aur_instance.suggest_pkgbase("zeu");
+	/// ```
+	pub fn suggest_pkgbase(
+		&self,
+		prefix: &str,
+	) -> Result<Vec<String>, AurError> {
+		let res =
+			self.send_raw(&Query::SuggestPkgbase(prefix.to_owned()))?;
+		Ok(serde_json::from_value(res)?)
+	}
+
+	/// Send a raw query through the transport of this instance.
+	pub fn send(&self, query: &Query) -> AurResult {
+		let res: AurResponse =
+			serde_json::from_value(self.send_raw(query)?)?;
+
+		match res.r#type.as_str() {
+			"error" => Err(AurError::from_rpc("unknown error")),
+			_ => Ok(res),
+		}
+	}
+
+	/// Send a query and return the response as is, unless it is an
+	/// error.
+	fn send_raw(&self, query: &Query) -> Result<Value, AurError> {
+		let res = match &self.transport.0 {
+			Some(v) => v.send(query)?,
+			// deserialized instances have no transport
+			None => HttpTransport::new(&self.rpc_url).send(query)?,
+		};
+
+		match res.get("error").and_then(|x| x.as_str()) {
+			Some(e) => Err(AurError::from_rpc(e)),
+			None => Ok(res),
+		}
+	}
+}
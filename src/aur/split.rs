@@ -0,0 +1,96 @@
+//! Split packages.
+//!
+//! A package base can build more than one package, like `foo` and
+//! `foo-docs`. The base is cloned and built once, and each package
+//! picks its own files from what the build produced.
+
+use super::Package;
+
+/// Name of the package in the package file at `path`, like `foo`
+/// for `/build/foo/foo-1:1.0-1-x86_64.pkg.tar.zst`
+///
+/// # Example:
+/// ```
+/// use zeus::aur::package_file_name;
+///
+/// assert_eq!(
+///     package_file_name("foo-bar-1.0-1-any.pkg.tar.zst"),
+///     Some("foo-bar")
+/// );
+/// ```
+#[allow(dead_code)]
+pub fn package_file_name(path: &str) -> Option<&str> {
+	let file = path.rsplit('/').next()?;
+
+	// only the name can contain dashes
+	file.rsplitn(4, '-').nth(3)
+}
+
+/// Group `packages`, sorted so that dependencies come first, by their
+/// package base.
+///
+/// Bases keep the order of their first package unless one of their
+/// packages depends on a base that comes later, then they move after
+/// it. Bases that depend on each other keep their order.
+#[allow(dead_code)]
+pub fn group_by_base(
+	packages: &[Package],
+) -> Vec<(&str, Vec<&Package>)> {
+	let mut bases: Vec<(&str, Vec<&Package>)> = vec![];
+
+	for package in packages {
+		let base = match package.base() {
+			Some(v) => v,
+			None => continue,
+		};
+
+		match bases.iter_mut().find(|x| x.0 == base) {
+			Some((_, v)) => v.push(package),
+			None => bases.push((base, vec![package])),
+		}
+	}
+
+	// the bases each base needs to be built first
+	let needs: Vec<Vec<usize>> = bases
+		.iter()
+		.enumerate()
+		.map(|(i, (_, base_packages))| {
+			(0..bases.len())
+				.filter(|j| *j != i)
+				.filter(|j| {
+					base_packages
+						.iter()
+						.flat_map(|x| x.build_dependencies())
+						.any(|dep| {
+							bases[*j]
+								.1
+								.iter()
+								.any(|x| x.satisfies(&dep))
+						})
+				})
+				.collect()
+		})
+		.collect();
+
+	let mut order: Vec<usize> = vec![];
+
+	while order.len() < bases.len() {
+		let pending = |i: &usize| !order.contains(i);
+
+		let next = (0..bases.len())
+			.filter(pending)
+			.find(|i| !needs[*i].iter().any(pending))
+			// a cycle, which the resolver has already ordered
+			.or_else(|| (0..bases.len()).find(pending));
+
+		match next {
+			Some(v) => order.push(v),
+			None => break,
+		}
+	}
+
+	let mut bases: Vec<Option<_>> =
+		bases.into_iter().map(Some).collect();
+
+	order.into_iter().filter_map(|i| bases[i].take()).collect()
+}
@@ -0,0 +1,210 @@
+//! Parser for `.SRCINFO` files.
+//!
+//! A `.SRCINFO` is a list of `key = value` lines. The `pkgbase`
+//! section comes first and holds what every package of the base
+//! shares, each `pkgname` section after it may override some of
+//! those values. Keys like `depends_x86_64` only apply to one
+//! architecture.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::{AurError, Package};
+
+/// Values of a section, keyed by field name with any architecture
+/// suffix
+type Fields = BTreeMap<String, Vec<String>>;
+
+/// A parsed `.SRCINFO`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SrcInfo {
+	/// Name of the package base
+	pub base: String,
+	/// Fields of the `pkgbase` section
+	base_fields: Fields,
+	/// `pkgname` sections in order, with the fields they override
+	names: Vec<(String, Fields)>,
+}
+
+#[allow(dead_code)]
+impl SrcInfo {
+	/// Parse the contents of a `.SRCINFO`.
+	///
+	/// ```
+	/// use zeus::aur::SrcInfo;
+	///
+	/// let srcinfo = SrcInfo::parse(
+	///     "pkgbase = foo\n\tpkgver = 1.0\n\tpkgrel = 1\n\npkgname = foo\n",
+	/// )
+	/// .unwrap();
+	///
+	/// assert_eq!(srcinfo.base, "foo");
+	/// assert_eq!(srcinfo.version(), "1.0-1");
+	/// ```
+	pub fn parse(s: &str) -> Result<Self, AurError> {
+		let mut srcinfo = Self::default();
+		let mut has_base = false;
+
+		for (i, line) in s.lines().enumerate() {
+			let line = line.trim();
+
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+
+			let (key, value) = match line.split_once('=') {
+				Some((key, value)) => (key.trim(), value.trim()),
+				None => {
+					return Err(AurError::SrcInfo(
+						i + 1,
+						format!(
+							"expected `key = value`, found `{}`",
+							line
+						),
+					))
+				},
+			};
+
+			match key {
+				"pkgbase" if !has_base => {
+					srcinfo.base = value.to_owned();
+					has_base = true;
+					continue;
+				},
+				"pkgbase" => {
+					return Err(AurError::SrcInfo(
+						i + 1,
+						"more than one pkgbase".to_owned(),
+					))
+				},
+				_ if !has_base => {
+					return Err(AurError::SrcInfo(
+						i + 1,
+						format!("`{}` before pkgbase", key),
+					))
+				},
+				"pkgname" => {
+					srcinfo
+						.names
+						.push((value.to_owned(), Fields::new()));
+					continue;
+				},
+				_ => {},
+			}
+
+			let fields = match srcinfo.names.last_mut() {
+				Some((_, fields)) => fields,
+				None => &mut srcinfo.base_fields,
+			};
+
+			// an empty value clears the field, which is how package
+			// sections override it with nothing
+			let values = fields.entry(key.to_owned()).or_default();
+			if !value.is_empty() {
+				values.push(value.to_owned());
+			}
+		}
+
+		if !has_base {
+			return Err(AurError::SrcInfo(
+				1,
+				"no pkgbase".to_owned(),
+			));
+		}
+
+		Ok(srcinfo)
+	}
+
+	/// Read the `.SRCINFO` of the package in `dir`.
+	pub fn read<P: AsRef<Path>>(dir: P) -> Result<Self, AurError> {
+		Self::parse(&fs::read_to_string(
+			dir.as_ref().join(".SRCINFO"),
+		)?)
+	}
+
+	/// Full version of the base, like `1:2.0-1`
+	pub fn version(&self) -> String {
+		let value = |key| {
+			self.base_fields
+				.get(key)
+				.and_then(|x| x.first())
+				.map(|x| x.as_str())
+				.unwrap_or_default()
+		};
+
+		match value("epoch") {
+			"" | "0" => {
+				format!("{}-{}", value("pkgver"), value("pkgrel"))
+			},
+			epoch => format!(
+				"{}:{}-{}",
+				epoch,
+				value("pkgver"),
+				value("pkgrel")
+			),
+		}
+	}
+
+	/// Names of the packages of the base
+	pub fn names(&self) -> impl Iterator<Item = &str> {
+		self.names.iter().map(|(name, _)| name.as_str())
+	}
+
+	/// The package `name` as built on `arch`, with the values of the
+	/// base it does not override.
+	pub fn package(&self, name: &str, arch: &str) -> Option<Package> {
+		let (name, fields) =
+			self.names.iter().find(|x| x.0 == name)?;
+
+		let get = |key: &str| -> Vec<String> {
+			let values = |key: &str| {
+				fields
+					.get(key)
+					.or_else(|| self.base_fields.get(key))
+					.cloned()
+					.unwrap_or_default()
+			};
+
+			let mut v = values(key);
+			v.extend(values(&format!("{}_{}", key, arch)));
+			v
+		};
+
+		let list =
+			|key: &str| Some(get(key)).filter(|x| !x.is_empty());
+		let single = |key: &str| get(key).into_iter().next();
+
+		Some(Package {
+			Name: Some(name.clone()),
+			PackageBase: Some(self.base.clone()),
+			Version: Some(self.version()),
+			Description: single("pkgdesc"),
+			URL: single("url"),
+			Depends: list("depends"),
+			MakeDepends: list("makedepends"),
+			OptDepends: list("optdepends"),
+			CheckDepends: list("checkdepends"),
+			Conflicts: list("conflicts"),
+			Provides: list("provides"),
+			Replaces: list("replaces"),
+			Groups: list("groups"),
+			License: list("license"),
+			..Default::default()
+		})
+	}
+
+	/// All packages of the base as built on `arch`
+	pub fn packages(&self, arch: &str) -> Vec<Package> {
+		self.names().filter_map(|x| self.package(x, arch)).collect()
+	}
+}
+
+impl FromStr for SrcInfo {
+	type Err = AurError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s)
+	}
+}
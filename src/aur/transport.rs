@@ -0,0 +1,309 @@
+//! Ways of talking to the AUR.
+//!
+//! [`Aur`](super::Aur) does not send requests itself, it hands a
+//! [`Query`] to an [`AurTransport`] and decodes whatever JSON comes
+//! back. [`HttpTransport`] talks to the RPC interface of a live
+//! instance, [`FileTransport`] answers from a directory of package
+//! files, for hosts that cannot reach the AUR.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use const_format::formatcp;
+use reqwest::blocking::{Client, ClientBuilder};
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use super::{AurError, By, DepSpec, Package};
+
+/// A request to the RPC interface
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Query {
+	/// Information about the packages with these exact names
+	Info(Vec<String>),
+	/// Packages matching the keywords
+	Search(By, Vec<String>),
+	/// Names of packages starting with a prefix
+	Suggest(String),
+	/// Names of package bases starting with a prefix
+	SuggestPkgbase(String),
+}
+
+/// Something that can answer AUR queries
+pub trait AurTransport: fmt::Debug + Send + Sync {
+	/// Run `query` and return the raw response, in the format of the
+	/// RPC interface.
+	fn send(&self, query: &Query) -> Result<Value, AurError>;
+}
+
+/// Transport for the RPC interface over HTTP
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+	client: Client,
+	rpc_url: String,
+}
+
+#[allow(dead_code)]
+impl HttpTransport {
+	/// Create a transport for the RPC endpoint at `rpc_url`, like
+	/// `https://aur.archlinux.org/rpc/?v=5`
+	pub fn new(rpc_url: &str) -> Self {
+		use crate::config;
+
+		Self {
+			client: ClientBuilder::new()
+				.user_agent(formatcp!(
+					"{}-{}",
+					config::NAME,
+					config::VERSION
+				))
+				.build()
+				.unwrap(),
+			rpc_url: rpc_url.to_owned(),
+		}
+	}
+}
+
+impl AurTransport for HttpTransport {
+	fn send(&self, query: &Query) -> Result<Value, AurError> {
+		let mut pairs: Vec<(&str, String)> = vec![];
+
+		match query {
+			Query::Info(packages) => {
+				pairs.push(("type", "info".to_owned()));
+
+				for package in packages {
+					pairs.push(("arg[]", package.clone()));
+				}
+			},
+			Query::Search(by, keywords) => {
+				pairs.push(("type", "search".to_owned()));
+				pairs.push(("by", by.to_string().to_lowercase()));
+
+				for keyword in keywords {
+					pairs.push(("arg", keyword.clone()));
+				}
+			},
+			Query::Suggest(prefix) => {
+				pairs.push(("type", "suggest".to_owned()));
+				pairs.push(("arg", prefix.clone()));
+			},
+			Query::SuggestPkgbase(prefix) => {
+				pairs.push(("type", "suggest-pkgbase".to_owned()));
+				pairs.push(("arg", prefix.clone()));
+			},
+		}
+
+		let res =
+			self.client.get(&self.rpc_url).query(&pairs).send()?;
+		let status = res.status();
+
+		if status == StatusCode::TOO_MANY_REQUESTS {
+			let retry_after = res
+				.headers()
+				.get(RETRY_AFTER)
+				.and_then(|x| x.to_str().ok())
+				.and_then(|x| x.trim().parse().ok());
+
+			return Err(AurError::RateLimited(retry_after));
+		}
+
+		let body = res.bytes()?;
+
+		let res: serde_json::Result<Value> =
+			serde_json::from_slice(&body);
+
+		match (status.is_success(), res) {
+			(true, res) => Ok(res?),
+			// keep the message of error responses
+			(false, Ok(v)) if v.get("error").is_some() => Ok(v),
+			(false, _) => Err(AurError::Status(status.as_u16())),
+		}
+	}
+}
+
+/// Transport that reads packages from a directory
+///
+/// The directory holds one `<name>.json` file per package, with the
+/// same contents as the package's entry in an `info` response.
+#[derive(Debug, Clone)]
+pub struct FileTransport {
+	dir: PathBuf,
+}
+
+#[allow(dead_code)]
+impl FileTransport {
+	/// Create a transport that reads packages from `dir`
+	pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+		Self { dir: dir.as_ref().to_owned() }
+	}
+
+	fn read_package(&self, path: &Path) -> Result<Package, AurError> {
+		Ok(serde_json::from_slice(&fs::read(path)?)?)
+	}
+
+	fn info(
+		&self,
+		names: &[String],
+	) -> Result<Vec<Package>, AurError> {
+		let mut packages = vec![];
+
+		for name in names {
+			// names come from the user, never leave the directory
+			if name.contains('/') {
+				continue;
+			}
+
+			let path = self.dir.join(format!("{}.json", name));
+
+			match self.read_package(&path) {
+				Ok(v) => packages.push(v),
+				Err(AurError::Io(e))
+					if e.kind() == io::ErrorKind::NotFound => {},
+				Err(e) => return Err(e),
+			}
+		}
+
+		Ok(packages)
+	}
+
+	fn search(
+		&self,
+		by: &By,
+		keywords: &[String],
+	) -> Result<Vec<Package>, AurError> {
+		// like the RPC interface, only the last keyword counts
+		let keyword = match keywords.last() {
+			Some(v) => v.to_lowercase(),
+			None => return Ok(vec![]),
+		};
+
+		Ok(self
+			.packages()?
+			.into_iter()
+			.filter(|x| search_matches(x, by, &keyword))
+			.collect())
+	}
+
+	/// Every package in the directory, sorted by name
+	fn packages(&self) -> Result<Vec<Package>, AurError> {
+		let mut paths = vec![];
+		for entry in fs::read_dir(&self.dir)? {
+			let path = entry?.path();
+
+			if path.extension().map(|x| x == "json").unwrap_or(false)
+			{
+				paths.push(path);
+			}
+		}
+		paths.sort_by(|a, b| a.file_stem().cmp(&b.file_stem()));
+
+		paths.iter().map(|x| self.read_package(x)).collect()
+	}
+}
+
+impl AurTransport for FileTransport {
+	fn send(&self, query: &Query) -> Result<Value, AurError> {
+		let (response_type, results) = match query {
+			Query::Info(names) => ("multiinfo", self.info(names)?),
+			Query::Search(by, keywords) => {
+				("search", self.search(by, keywords)?)
+			},
+			Query::Suggest(prefix) => {
+				let packages = self.packages()?;
+				return Ok(suggestions(
+					packages.iter().filter_map(|x| x.Name.as_deref()),
+					prefix,
+				));
+			},
+			Query::SuggestPkgbase(prefix) => {
+				let packages = self.packages()?;
+				return Ok(suggestions(
+					packages
+						.iter()
+						.filter_map(|x| x.PackageBase.as_deref()),
+					prefix,
+				));
+			},
+		};
+
+		Ok(response(response_type, &results))
+	}
+}
+
+/// Build a response like the ones of the RPC interface
+pub(super) fn response(
+	response_type: &str,
+	results: &[Package],
+) -> Value {
+	json!({
+		"resultcount": results.len(),
+		"results": results,
+		"type": response_type,
+		"version": 5,
+	})
+}
+
+/// Answer a `suggest` query from `names`
+pub(super) fn suggestions<'a, I>(names: I, prefix: &str) -> Value
+where
+	I: Iterator<Item = &'a str>,
+{
+	let mut names: Vec<&str> =
+		names.filter(|x| x.starts_with(prefix)).collect();
+
+	// the AUR never suggests more than 20 names
+	names.sort_unstable();
+	names.dedup();
+	names.truncate(20);
+
+	json!(names)
+}
+
+/// Length of `s` once it is encoded for a query string
+pub(super) fn encoded_len(s: &str) -> usize {
+	s.bytes()
+		.map(|x| match x {
+			b'*' | b'-' | b'.' | b'_' | b' ' => 1,
+			x if x.is_ascii_alphanumeric() => 1,
+			_ => 3,
+		})
+		.sum()
+}
+
+/// Check whether `package` matches the lowercase `keyword` of a
+/// search by `by`, the way the RPC interface does it.
+pub(super) fn search_matches(
+	package: &Package,
+	by: &By,
+	keyword: &str,
+) -> bool {
+	let contains = |x: &Option<String>| {
+		x.as_ref()
+			.map(|x| x.to_lowercase().contains(keyword))
+			.unwrap_or(false)
+	};
+
+	let depends_on = |x: &Option<Vec<String>>| {
+		x.iter().flatten().any(|x| DepSpec::from(x).name == keyword)
+	};
+
+	match by {
+		By::Name => contains(&package.Name),
+		By::NameDesc => {
+			contains(&package.Name) || contains(&package.Description)
+		},
+		By::Maintainer => package
+			.Maintainer
+			.as_ref()
+			.map(|x| x.to_lowercase() == keyword)
+			.unwrap_or(false),
+		By::Depends => depends_on(&package.Depends),
+		By::MakeDepends => depends_on(&package.MakeDepends),
+		By::OptDepends => depends_on(&package.OptDepends),
+		By::CheckDepends => depends_on(&package.CheckDepends),
+	}
+}
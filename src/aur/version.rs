@@ -0,0 +1,290 @@
+//! Arch Linux package versions.
+//!
+//! This is a port of `alpm_pkg_vercmp` from libalpm, so versions
+//! compare exactly like they do with pacman's `vercmp`.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A package version in the form of `[epoch:]pkgver[-pkgrel]`
+///
+/// Versions are totally ordered, with a version without a `pkgrel`
+/// older than the same version with any `pkgrel`. Use
+/// [`Version::vercmp`] to compare them like libalpm, where `1.0`
+/// matches `1.0-2`.
+///
+/// # Example:
+/// ```
+/// use zeus::aur::Version;
+///
+/// let a = Version::from("1:1.0-1");
+/// let b = Version::from("2.0-1");
+///
+/// assert_eq!(a.epoch(), "1");
+/// assert_eq!(a.pkgver(), "1.0");
+/// assert_eq!(a.pkgrel(), Some("1"));
+/// assert!(a > b);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct Version {
+	full: String,
+
+	epoch: String,
+	pkgver: String,
+	pkgrel: Option<String>,
+}
+
+#[allow(dead_code)]
+impl Version {
+	/// The epoch, `0` if there is none
+	pub fn epoch(&self) -> &str {
+		&self.epoch
+	}
+
+	/// The version of the software
+	pub fn pkgver(&self) -> &str {
+		&self.pkgver
+	}
+
+	/// The release of the package
+	pub fn pkgrel(&self) -> Option<&str> {
+		self.pkgrel.as_deref()
+	}
+
+	/// The version exactly as it was parsed
+	pub fn as_str(&self) -> &str {
+		&self.full
+	}
+
+	/// Compare with `other` like libalpm, where a missing `pkgrel`
+	/// matches any `pkgrel`.
+	///
+	/// Unlike [`Ord`], this is not transitive: `1.0-1` and `1.0-2`
+	/// both match `1.0`, but not each other.
+	///
+	/// # Example:
+	/// ```
+	/// use std::cmp::Ordering;
+	/// use zeus::aur::Version;
+	///
+	/// let a = Version::from("1.0");
+	/// let b = Version::from("1.0-2");
+	///
+	/// assert_eq!(a.vercmp(&b), Ordering::Equal);
+	/// assert!(a < b);
+	/// ```
+	pub fn vercmp(&self, other: &Self) -> Ordering {
+		self.cmp_with(other, Ordering::Equal)
+	}
+
+	/// Compare with `other`, with `missing_pkgrel` as the result when
+	/// only one of the versions has a `pkgrel`, from the side of the
+	/// one without.
+	fn cmp_with(
+		&self,
+		other: &Self,
+		missing_pkgrel: Ordering,
+	) -> Ordering {
+		if self.full == other.full {
+			return Ordering::Equal;
+		}
+
+		rpmvercmp(&self.epoch, &other.epoch)
+			.then_with(|| rpmvercmp(&self.pkgver, &other.pkgver))
+			.then_with(|| match (&self.pkgrel, &other.pkgrel) {
+				(Some(a), Some(b)) => rpmvercmp(a, b),
+				(None, Some(_)) => missing_pkgrel,
+				(Some(_), None) => missing_pkgrel.reverse(),
+				(None, None) => Ordering::Equal,
+			})
+	}
+}
+
+impl From<&str> for Version {
+	fn from(s: &str) -> Self {
+		// the epoch can only be made of digits
+		let epoch_end =
+			s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+		let release_start = s.rfind('-');
+
+		let (epoch, version_start) =
+			match s[epoch_end..].starts_with(':') {
+				true if epoch_end == 0 => ("0", epoch_end + 1),
+				true => (&s[..epoch_end], epoch_end + 1),
+				false => ("0", 0),
+			};
+
+		let (pkgver, pkgrel) = match release_start {
+			Some(i) if i >= version_start => {
+				(&s[version_start..i], Some(&s[i + 1..]))
+			},
+			_ => (&s[version_start..], None),
+		};
+
+		Self {
+			full: s.to_owned(),
+			epoch: epoch.to_owned(),
+			pkgver: pkgver.to_owned(),
+			pkgrel: pkgrel.map(|x| x.to_owned()),
+		}
+	}
+}
+
+impl From<String> for Version {
+	fn from(s: String) -> Self {
+		Self::from(s.as_str())
+	}
+}
+
+impl From<Version> for String {
+	fn from(v: Version) -> Self {
+		v.full
+	}
+}
+
+impl fmt::Display for Version {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.full)
+	}
+}
+
+impl Ord for Version {
+	fn cmp(&self, other: &Self) -> Ordering {
+		// a missing pkgrel is older, to keep the order total
+		self.cmp_with(other, Ordering::Less)
+	}
+}
+
+impl PartialOrd for Version {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl PartialEq for Version {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Version {}
+
+/// Compare two version strings like pacman's `vercmp`.
+///
+/// # Example:
+/// ```
+/// use std::cmp::Ordering;
+/// use zeus::aur::vercmp;
+///
+/// assert_eq!(vercmp("1.0rc", "1.0"), Ordering::Less);
+/// ```
+#[allow(dead_code)]
+pub fn vercmp(a: &str, b: &str) -> Ordering {
+	Version::from(a).vercmp(&Version::from(b))
+}
+
+/// Compare two version segments, without any epoch or release.
+///
+/// The version is split into alternating runs of digits and letters,
+/// everything else is a separator. Runs of digits compare as numbers,
+/// runs of letters compare as strings and a run of digits is always
+/// newer than a run of letters.
+fn rpmvercmp(a: &str, b: &str) -> Ordering {
+	if a == b {
+		return Ordering::Equal;
+	}
+
+	let (a, b) = (a.as_bytes(), b.as_bytes());
+
+	// `one` and `two` point to the start of the current segment,
+	// `ptr1` and `ptr2` to the end of the previous one
+	let (mut one, mut two) = (0, 0);
+	let (mut ptr1, mut ptr2) = (0, 0);
+
+	while one < a.len() && two < b.len() {
+		while one < a.len() && !a[one].is_ascii_alphanumeric() {
+			one += 1;
+		}
+		while two < b.len() && !b[two].is_ascii_alphanumeric() {
+			two += 1;
+		}
+
+		if one >= a.len() || two >= b.len() {
+			break;
+		}
+
+		// if the separators have different lengths, the longer one wins
+		if one - ptr1 != two - ptr2 {
+			return (one - ptr1).cmp(&(two - ptr2));
+		}
+
+		ptr1 = one;
+		ptr2 = two;
+
+		let is_num = a[ptr1].is_ascii_digit();
+		let in_segment = |c: &u8| match is_num {
+			true => c.is_ascii_digit(),
+			false => c.is_ascii_alphabetic(),
+		};
+
+		while ptr1 < a.len() && in_segment(&a[ptr1]) {
+			ptr1 += 1;
+		}
+		while ptr2 < b.len() && in_segment(&b[ptr2]) {
+			ptr2 += 1;
+		}
+
+		// the segments are of different types, numbers are newer
+		if two == ptr2 {
+			return match is_num {
+				true => Ordering::Greater,
+				false => Ordering::Less,
+			};
+		}
+
+		let mut seg1 = &a[one..ptr1];
+		let mut seg2 = &b[two..ptr2];
+
+		if is_num {
+			while seg1.first() == Some(&b'0') {
+				seg1 = &seg1[1..];
+			}
+			while seg2.first() == Some(&b'0') {
+				seg2 = &seg2[1..];
+			}
+
+			// whichever number has more digits wins
+			match seg1.len().cmp(&seg2.len()) {
+				Ordering::Equal => {},
+				x => return x,
+			}
+		}
+
+		match seg1.cmp(seg2) {
+			Ordering::Equal => {},
+			x => return x,
+		}
+
+		one = ptr1;
+		two = ptr2;
+	}
+
+	if one >= a.len() && two >= b.len() {
+		return Ordering::Equal;
+	}
+
+	// a remaining run of letters never beats an empty string, so
+	// `1.0rc` is older than `1.0`, but `1.0.1` is newer
+	let is_alpha = |s: &[u8], i: usize| {
+		s.get(i).map(|x| x.is_ascii_alphabetic()).unwrap_or(false)
+	};
+
+	if (one >= a.len() && !is_alpha(b, two)) || is_alpha(a, one) {
+		Ordering::Less
+	} else {
+		Ordering::Greater
+	}
+}
@@ -4,15 +4,18 @@ use std::os::unix::net::UnixStream;
 
 mod aur;
 mod config;
+mod db;
 mod error;
 mod log;
 mod machine;
 mod message;
 mod unix;
 
-use aur::Package;
+use aur::{group_by_base, package_file_name, Package};
+use channels::Sender;
 use colored::Colorize;
 use config::{AppConfig, Operation};
+use db::{BuildResult, Entry};
 use error::{Result, ZeusError};
 use message::Message;
 
@@ -42,8 +45,8 @@ fn run_command(arg0: &str, args: &[&str]) -> Result<ExitStatus> {
 	Ok(status)
 }
 
-fn update_package() -> Result<()> {
-	let r = run_command("git", &["pull", "-f"])?;
+fn run_git(args: &[&str]) -> Result<()> {
+	let r = run_command("git", args)?;
 
 	if !r.success() {
 		return Err(ZeusError::new(
@@ -55,13 +58,18 @@ fn update_package() -> Result<()> {
 	Ok(())
 }
 
-fn clone_package(cfg: &AppConfig, package_name: &str) -> Result<()> {
+fn update_package() -> Result<()> {
+	run_git(&["pull", "-f"])
+}
+
+/// Clone the git repository of the package base `base`.
+fn clone_package(cfg: &AppConfig, base: &str) -> Result<()> {
 	let status = run_command(
 		"git",
 		&[
 			"clone",
 			"--",
-			&format!("{}/{}.git", cfg.aur.get_url(), &package_name),
+			&format!("{}{}.git", cfg.aur.get_url(), base),
 		],
 	)?;
 
@@ -123,61 +131,219 @@ fn get_package_files() -> Result<Vec<String>> {
 		.collect())
 }
 
-fn build_package(
-	cfg: &AppConfig,
-	package_name: &str,
-) -> Result<bool> {
+fn build_package(cfg: &AppConfig, base: &str) -> Result<bool> {
 	use std::path::Path;
-	if !Path::new(package_name).exists() {
-		clone_package(&cfg, package_name)?;
+	if !Path::new(base).exists() {
+		clone_package(&cfg, base)?;
 	}
 
-	chdir(package_name)?;
+	chdir(base)?;
 
-	if cfg.upgrade {
-		update_package()?;
+	// fetched packages are built at the commit the host decided on
+	match cfg.commits.get(base) {
+		Some(commit) => run_git(&["reset", "-q", "--hard", commit])?,
+		None if cfg.upgrade => update_package()?,
+		None => {},
 	}
 
 	make_package(&cfg)
 }
 
+/// Clone the package base `base` or fetch its new commits, without
+/// checking them out.
+///
+/// Returns the commit that would be built.
+fn fetch_package(cfg: &AppConfig, base: &str) -> Result<String> {
+	use std::path::Path;
+
+	let synced = Path::new(base).exists();
+	if !synced {
+		clone_package(&cfg, base)?;
+	}
+
+	chdir(base)?;
+
+	// only upgrades update packages that are already synced
+	let rev = match synced && cfg.upgrade {
+		true => {
+			run_git(&["fetch", "-q", "origin", "HEAD"])?;
+			"FETCH_HEAD"
+		},
+		false => "HEAD",
+	};
+
+	get_commit(rev).ok_or_else(|| {
+		ZeusError::new(
+			"builder".to_owned(),
+			format!("Cannot find the {} of {}", rev, base),
+		)
+	})
+}
+
+/// Fetch the package bases of `cfg.packages` and report the commit
+/// each one would be built at. The host reads them before deciding
+/// what to build.
+fn fetch_packages(
+	cfg: &AppConfig,
+	build_root: &str,
+	tx: &mut Sender<Message, UnixStream>,
+) -> Result<Vec<Package>> {
+	for (base, _) in group_by_base(&cfg.packages) {
+		info!("builder", "Fetching package: {}", base);
+
+		chdir(build_root)?;
+
+		match fetch_package(cfg, base) {
+			Ok(commit) => {
+				tx.send(Message::Fetched(base.to_owned(), commit))?
+			},
+			Err(e) => warning!("builder", "{}", e),
+		}
+	}
+
+	Ok(vec![])
+}
+
+fn install_package_files(files: &[String]) -> Result<()> {
+	let files: Vec<&str> = files
+		.iter()
+		.filter(|x| std::path::Path::new(x).exists())
+		.map(|x| x.as_str())
+		.collect();
+
+	if files.is_empty() {
+		return Ok(());
+	}
+
+	let status = run_command(
+		"sudo",
+		&[
+			&["pacman", "-U", "--noconfirm", "--needed", "--asdeps"],
+			files.as_slice(),
+		]
+		.concat(),
+	)?;
+
+	if !status.success() {
+		return Err(ZeusError::new(
+			"builder".to_owned(),
+			format!(
+				"pacman failed with: {}",
+				status.code().unwrap_or(-1)
+			),
+		));
+	}
+
+	Ok(())
+}
+
+fn get_commit(rev: &str) -> Option<String> {
+	use std::process::Command;
+
+	let output =
+		Command::new("git").args(["rev-parse", rev]).output().ok()?;
+
+	if !output.status.success() {
+		return None;
+	}
+
+	Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
 fn build_packages(
 	cfg: &AppConfig,
 	build_root: &str,
+	tx: &mut Sender<Message, UnixStream>,
 ) -> Result<Vec<Package>> {
 	let mut packages: Vec<Package> = vec![];
-	for package in &cfg.packages {
-		if let Some(pkg_name) = package.Name.as_ref() {
-			info!("builder", "Building package: {}", pkg_name);
 
-			chdir(build_root)?;
+	// split packages are built together, once for their base
+	let bases = group_by_base(&cfg.packages);
 
-			let mut pkg = package.clone();
+	for (i, (base, base_packages)) in bases.iter().enumerate() {
+		info!("builder", "Building package: {}", base);
 
-			let pkg_built = match build_package(&cfg, pkg_name) {
-				Err(e) => {
-					warning!("builder", "{}", e);
-					continue;
-				},
-				Ok(v) => v,
-			};
-
-			if cfg.install {
-				pkg.package_files = Some(match get_package_files() {
-					Ok(v) => v,
-					Err(e) => {
-						warning!(
-							"builder",
-							"Could not get package files: {}",
-							e
-						);
-						vec![]
-					},
+		chdir(build_root)?;
+
+		let pkg_built = match build_package(&cfg, base) {
+			Err(e) => {
+				warning!("builder", "{}", e);
+				for package in base_packages {
+					tx.send(Message::Built(Entry::new(
+						package,
+						BuildResult::Failed,
+					)))?;
+				}
+				continue;
+			},
+			Ok(v) => v,
+		};
+
+		let result = match pkg_built {
+			true => BuildResult::Built,
+			false => BuildResult::UpToDate,
+		};
+		let commit = get_commit("HEAD");
+		let files = match get_package_files() {
+			Ok(v) => v,
+			Err(e) => {
+				warning!(
+					"builder",
+					"Could not get package files: {}",
+					e
+				);
+				vec![]
+			},
+		};
+
+		for package in base_packages {
+			let mut entry = Entry::new(package, result);
+			entry.commit = commit.clone();
+
+			// the base builds every split package, but only the
+			// requested ones are installed
+			entry.files = files
+				.iter()
+				.filter(|x| {
+					package_file_name(x) == package.Name.as_deref()
 				})
+				.cloned()
+				.collect();
+
+			// packages later in the batch cannot be built until
+			// this one is installed if they depend on it
+			let needed = bases[i + 1..]
+				.iter()
+				.flat_map(|x| x.1.iter())
+				.any(|x| {
+					x.build_dependencies()
+						.any(|dep| package.satisfies(&dep))
+				});
+
+			if needed {
+				let pkg_name =
+					package.Name.as_deref().unwrap_or(base);
+
+				info!(
+					"builder",
+					"Installing {} for the remaining packages",
+					pkg_name
+				);
+
+				if let Err(e) = install_package_files(&entry.files) {
+					warning!(
+						"builder",
+						"Could not install package {}: {}",
+						pkg_name,
+						e
+					);
+				}
 			}
 
+			tx.send(Message::Built(entry))?;
+
 			if pkg_built {
-				packages.push(pkg);
+				packages.push((*package).clone());
 			}
 		}
 	}
@@ -194,35 +360,30 @@ fn remove_packages(
 
 	use std::fs;
 	use std::path::Path;
-	for package in &cfg.packages {
-		if let Some(pkg_name) = package.Name.as_ref() {
-			info!("zeus", "Removing package: {}", pkg_name);
 
-			let pkg_path = Path::new(pkg_name);
+	// split packages share the directory of their base
+	for (base, base_packages) in group_by_base(&cfg.packages) {
+		info!("zeus", "Removing package: {}", base);
 
-			let pkg = package.clone();
+		let pkg_path = Path::new(base);
 
-			if pkg_path.exists() && pkg_path.is_dir() {
-				match fs::remove_dir_all(pkg_path) {
-					Ok(_) => {
-						removed_packages.push(pkg);
-					},
-					Err(e) => {
-						warning!(
+		if pkg_path.exists() && pkg_path.is_dir() {
+			match fs::remove_dir_all(pkg_path) {
+				Ok(_) => {
+					removed_packages
+						.extend(base_packages.into_iter().cloned());
+				},
+				Err(e) => {
+					warning!(
 						"fs",
 						"Cannot remove package directory \"{}\": {}",
 						pkg_path.display(),
 						e
 					);
-					},
-				}
-			} else {
-				warning!(
-					"zeus",
-					"Package {} has not been synced",
-					pkg_name
-				);
+				},
 			}
+		} else {
+			warning!("zeus", "Package {} has not been synced", base);
 		}
 	}
 
@@ -270,8 +431,9 @@ fn main() {
 	}
 
 	let op_res = match cfg.operation {
-		Operation::Sync => build_packages(&cfg, "/build"),
+		Operation::Sync => build_packages(&cfg, "/build", &mut tx),
 		Operation::Remove => remove_packages(&cfg, "/build"),
+		Operation::Fetch => fetch_packages(&cfg, "/build", &mut tx),
 		_ => {
 			error!(
 				"builder",
@@ -1,6 +1,6 @@
 use crate::config::{self, defaults};
 
-use clap::{Arg, Command};
+use clap::{Arg, ArgMatches, Command};
 
 use std::io::Write;
 
@@ -29,6 +29,12 @@ pub fn build_subcommands() -> Vec<Command<'static>> {
 					.help("Install packages after build")
 					.takes_value(false),
 			)
+			.arg(
+				Arg::new("no-review")
+					.long("no-review")
+					.help("Do not review packages before building them")
+					.takes_value(false),
+			)
 			.arg(
 				Arg::new("buildargs")
 					.long("buildargs")
@@ -114,13 +120,99 @@ pub fn build_subcommands() -> Vec<Command<'static>> {
 					.default_value("description")
 					.conflicts_with("info"),
 			)
+			.arg(
+				Arg::new("local")
+					.short('l')
+					.long("local")
+					.help("Query synced packages")
+					.takes_value(false)
+					.conflicts_with_all(&["info", "by"]),
+			)
+			.arg(
+				Arg::new("sort")
+					.long("sort")
+					.help("Sort results by")
+					.possible_values([
+						"votes",
+						"popularity",
+						"name",
+						"modified",
+					])
+					.conflicts_with("local"),
+			)
+			.arg(
+				Arg::new("reverse")
+					.long("reverse")
+					.help("Reverse the order of results")
+					.takes_value(false)
+					.conflicts_with("local"),
+			)
+			.arg(
+				Arg::new("limit")
+					.long("limit")
+					.help("Show at most this many results")
+					.takes_value(true)
+					.validator(|x| x.parse::<usize>())
+					.conflicts_with("local"),
+			)
+			.arg(
+				Arg::new("maintained-only")
+					.long("maintained-only")
+					.help("Hide orphaned packages")
+					.takes_value(false)
+					.conflicts_with("local"),
+			)
+			.arg(
+				Arg::new("not-out-of-date")
+					.long("not-out-of-date")
+					.help("Hide packages flagged out of date")
+					.takes_value(false)
+					.conflicts_with("local"),
+			)
+			.arg(
+				Arg::new("min-votes")
+					.long("min-votes")
+					.help("Hide packages with fewer votes")
+					.takes_value(true)
+					.validator(|x| x.parse::<u64>())
+					.conflicts_with("local"),
+			)
+			.arg(
+				Arg::new("date-format")
+					.long("date-format")
+					.help("Show dates in local time or as ISO-8601")
+					.possible_values(["local", "iso"])
+					.default_value("local"),
+			)
 			.arg(
 				Arg::new("output")
 					.long("output")
 					.help("Output format")
-					.possible_values(["pretty", "json"])
+					.possible_values(["pretty", "plain", "tsv", "json"])
 					.default_value("pretty"),
 			)
+			.arg(
+				Arg::new("format")
+					.long("format")
+					.help("Print results with a template like '{Name} {Version}'")
+					.takes_value(true)
+					.conflicts_with_all(&["output", "local"]),
+			)
+			.arg(
+				Arg::new("select")
+					.long("select")
+					.help("Choose packages to sync and install from the results")
+					.takes_value(false)
+					.conflicts_with_all(&[
+						"info", "local", "output", "format",
+					]),
+			)
+			.arg(
+				Arg::new("name")
+					.long("name")
+					.help("Builder machine name for --select")
+					.default_value(defaults::BUILDER_NAME),
+			)
 			.arg(
 				Arg::new("keywords")
 					.help("Keywords to use")
@@ -135,6 +227,14 @@ pub fn build_subcommands() -> Vec<Command<'static>> {
 					.long("shell")
 					.help("Specify shell to generate completions for")
 					.takes_value(true),
+			)
+			.arg(
+				Arg::new("packages")
+					.long("packages")
+					.help("List AUR packages starting with a prefix")
+					.takes_value(true)
+					.hide(true)
+					.conflicts_with("shell"),
 			),
 		////////////////////////////////////////////////////
 		Command::new("runtime")
@@ -222,6 +322,32 @@ pub fn build() -> Command<'static> {
 				.help("AUR host")
 				.default_value(defaults::AUR_HOST),
 		)
+		.arg(
+			Arg::new("aurdir")
+				.long("aurdir")
+				.help("Read AUR package data from a directory")
+				.takes_value(true),
+		)
+		.arg(
+			Arg::new("archive")
+				.long("archive")
+				.help("Use the AUR metadata archive instead of the RPC interface")
+				.takes_value(false)
+				.conflicts_with("aurdir"),
+		)
+		.arg(
+			Arg::new("cache-ttl")
+				.long("cache-ttl")
+				.help("Seconds to reuse cached AUR responses for")
+				.validator(|x| x.parse::<u64>())
+				.default_value("600"),
+		)
+		.arg(
+			Arg::new("refresh")
+				.long("refresh")
+				.help("Ignore cached AUR responses")
+				.takes_value(false),
+		)
 		.arg(
 			Arg::new("rt")
 				.long("rt")
@@ -234,10 +360,102 @@ pub fn build() -> Command<'static> {
 				.help("Specify directory to search for runtimes")
 				.default_value(defaults::RUNTIME_DIR),
 		)
+		// `zeus <keywords>` parses like an unknown subcommand, so it
+		// never shadows the real ones
+		.allow_external_subcommands(true)
+		.after_help(
+			"Run `zeus <keywords>...` to search the AUR and choose packages to sync.",
+		)
 		.subcommand_required(true)
+		.arg_required_else_help(true)
 		.subcommands(build_subcommands())
 }
 
+/// The subcommand to run and its arguments.
+///
+/// Anything that is not a subcommand is a list of keywords, and runs
+/// `query --select` with them.
+pub fn subcommand(args: &ArgMatches) -> (String, ArgMatches) {
+	// subcommand_required is set in build()
+	let (name, sub_args) = args.subcommand().unwrap();
+
+	if build_subcommands().iter().any(|x| x.get_name() == name) {
+		return (name.to_owned(), sub_args.clone());
+	}
+
+	let keywords = std::iter::once(name)
+		.chain(sub_args.values_of("").unwrap_or_default());
+
+	("query".to_owned(), select_query(keywords))
+}
+
+/// Arguments of `query --select` with `keywords`
+fn select_query<'a, I>(keywords: I) -> ArgMatches
+where
+	I: Iterator<Item = &'a str>,
+{
+	build_subcommands()
+		.into_iter()
+		.find(|x| x.get_name() == "query")
+		.unwrap()
+		.get_matches_from(
+			["query", "--select"].into_iter().chain(keywords),
+		)
+}
+
+// Complete AUR package names for `sync` with `completions --packages`
+const BASH_PACKAGES: &str = r#"
+_zeus_packages() {
+    _zeus "$@"
+
+    local cur="${COMP_WORDS[COMP_CWORD]}" i
+    [[ -z ${cur} || ${cur} == -* ]] && return 0
+
+    for i in "${COMP_WORDS[@]:1:COMP_CWORD-1}"; do
+        case "${i}" in
+            sync|--sync|-S*)
+                COMPREPLY+=( $(zeus completions --packages "${cur}" 2>/dev/null) )
+                return 0
+                ;;
+        esac
+    done
+}
+
+complete -F _zeus_packages -o bashdefault -o default zeus
+"#;
+
+const ZSH_PACKAGES: &str = r#"_zeus_packages() {
+    local -a packages
+    packages=(${(f)"$(zeus completions --packages "$PREFIX" 2>/dev/null)"})
+    compadd -a packages
+}
+
+"#;
+
+const FISH_PACKAGES: &str = r#"complete -c zeus -n "__fish_seen_subcommand_from sync" -f -a "(zeus completions --packages (commandline -ct) 2>/dev/null)"
+"#;
+
 pub fn make_completions(s: Shell, buf: &mut dyn Write) {
-	generate(s, &mut build(), config::NAME, buf);
+	let mut script = vec![];
+	generate(s, &mut build(), config::NAME, &mut script);
+	let mut script = String::from_utf8_lossy(&script).into_owned();
+
+	match s {
+		Shell::Bash => script.push_str(BASH_PACKAGES),
+		Shell::Zsh => {
+			script = script.replace(
+				"'*::packages -- Packages to sync:'",
+				"'*::packages -- Packages to sync:_zeus_packages'",
+			);
+
+			// the functions must exist before _zeus runs
+			if let Some(i) = script.rfind("_zeus \"$@\"") {
+				script.insert_str(i, ZSH_PACKAGES);
+			}
+		},
+		Shell::Fish => script.push_str(FISH_PACKAGES),
+		_ => {},
+	}
+
+	let _ = buf.write_all(script.as_bytes());
 }
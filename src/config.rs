@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use crate::aur::{Aur, Package};
 
 use serde::{Deserialize, Serialize};
@@ -43,6 +45,8 @@ pub mod defaults {
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Operation {
 	Sync,
+	/// Fetch packages before a sync, without building them
+	Fetch,
 	Remove,
 	Build,
 	Query,
@@ -99,6 +103,12 @@ pub struct AppConfig {
 	pub upgrade: bool,
 	pub build_args: Vec<String>,
 
+	/// Review packages on the host before building them
+	pub review: bool,
+
+	/// Commits to build, keyed by package base
+	pub commits: BTreeMap<String, String>,
+
 	// Should we install the packages to the host?
 	pub install: bool,
 
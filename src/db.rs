@@ -0,0 +1,210 @@
+//! Local database of synced packages.
+//!
+//! The database lives in the build directory and keeps track of
+//! what was built, from which commit and what it produced, so the
+//! rest of zeus does not have to guess from the directory layout.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::aur::{Package, Timestamp};
+
+const DB_NAME: &str = ".zeus.db";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BuildResult {
+	/// The package was built
+	Built,
+	/// makepkg found the package already built
+	UpToDate,
+	/// The build failed
+	Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+	/// Package name
+	pub name: String,
+	/// AUR package base
+	pub base: Option<String>,
+	/// AUR version the package was built from
+	pub version: Option<String>,
+	/// Git commit the package was built from
+	pub commit: Option<String>,
+	/// Package files produced by the build
+	pub files: Vec<String>,
+	/// Time of the last build
+	pub built: Timestamp,
+	/// Result of the last build
+	pub result: BuildResult,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Database {
+	#[serde(skip)]
+	path: PathBuf,
+
+	pub packages: BTreeMap<String, Entry>,
+
+	/// Last reviewed git commit of each package base
+	#[serde(default)]
+	pub reviewed: BTreeMap<String, String>,
+}
+
+/// Get the current time as a Unix timestamp.
+pub fn now() -> Timestamp {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|x| x.as_secs())
+		.unwrap_or(0)
+}
+
+#[allow(dead_code)]
+impl Entry {
+	pub fn new(package: &Package, result: BuildResult) -> Self {
+		Self {
+			name: package.Name.clone().unwrap_or_default(),
+			base: package.PackageBase.clone(),
+			version: package.Version.clone(),
+			commit: None,
+			files: vec![],
+			built: now(),
+			result,
+		}
+	}
+
+	/// Name of the package base, which is also the name of the
+	/// package directory
+	pub fn base(&self) -> &str {
+		self.base.as_deref().unwrap_or(&self.name)
+	}
+}
+
+#[allow(dead_code)]
+impl Database {
+	/// Open the database in `build_dir`.
+	///
+	/// If the database does not exist yet, it is created from the
+	/// packages already present in `build_dir`.
+	pub fn open<P: AsRef<Path>>(build_dir: P) -> io::Result<Self> {
+		let path = build_dir.as_ref().join(DB_NAME);
+
+		if !path.exists() {
+			let mut db = Self { path, ..Default::default() };
+			db.import(build_dir.as_ref())?;
+			return Ok(db);
+		}
+
+		let mut db: Self = serde_json::from_slice(&fs::read(&path)?)
+			.map_err(|e| {
+				io::Error::new(io::ErrorKind::InvalidData, e)
+			})?;
+		db.path = path;
+
+		Ok(db)
+	}
+
+	/// Add entries for package directories that have been synced
+	/// before the database existed.
+	fn import(&mut self, build_dir: &Path) -> io::Result<()> {
+		for entry in fs::read_dir(build_dir)? {
+			let entry = match entry {
+				Ok(v) => v,
+				Err(_) => continue,
+			};
+
+			if !entry.path().join("PKGBUILD").exists() {
+				continue;
+			}
+
+			if let Ok(name) = entry.file_name().into_string() {
+				self.packages.insert(
+					name.clone(),
+					Entry {
+						name,
+						base: None,
+						version: None,
+						commit: None,
+						files: vec![],
+						built: 0,
+						result: BuildResult::Built,
+					},
+				);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Write the database back to disk.
+	pub fn save(&self) -> io::Result<()> {
+		let tmp_path = self.path.with_extension("tmp");
+
+		fs::write(
+			&tmp_path,
+			serde_json::to_vec_pretty(self).map_err(|e| {
+				io::Error::new(io::ErrorKind::InvalidData, e)
+			})?,
+		)?;
+
+		fs::rename(&tmp_path, &self.path)
+	}
+
+	pub fn get(&self, name: &str) -> Option<&Entry> {
+		self.packages.get(name)
+	}
+
+	pub fn contains(&self, name: &str) -> bool {
+		self.packages.contains_key(name)
+	}
+
+	/// Record the result of a build.
+	///
+	/// A failed build does not replace the information of the last
+	/// successful one, since those are the files still on disk.
+	pub fn record(&mut self, entry: Entry) {
+		match self.packages.get_mut(&entry.name) {
+			Some(old) if entry.result == BuildResult::Failed => {
+				old.built = entry.built;
+				old.result = entry.result;
+			},
+			_ => {
+				self.packages.insert(entry.name.clone(), entry);
+			},
+		}
+	}
+
+	pub fn remove(&mut self, name: &str) -> Option<Entry> {
+		let entry = self.packages.remove(name)?;
+
+		if self.by_base(entry.base()).next().is_none() {
+			self.reviewed.remove(entry.base());
+		}
+
+		Some(entry)
+	}
+
+	/// Get the synced packages of the package base `base`.
+	pub fn by_base<'a>(
+		&'a self,
+		base: &'a str,
+	) -> impl Iterator<Item = &'a Entry> {
+		self.packages.values().filter(move |x| x.base() == base)
+	}
+
+	/// Get the last reviewed commit of the package base `base`.
+	pub fn reviewed(&self, base: &str) -> Option<&str> {
+		self.reviewed.get(base).map(|x| x.as_str())
+	}
+
+	/// Remember that `commit` of the package base `base` has been
+	/// reviewed.
+	pub fn set_reviewed(&mut self, base: &str, commit: &str) {
+		self.reviewed.insert(base.to_owned(), commit.to_owned());
+	}
+}
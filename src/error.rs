@@ -50,6 +50,15 @@ impl From<crate::machine::Error> for ZeusError {
 	}
 }
 
+impl From<crate::aur::AurError> for ZeusError {
+	fn from(e: crate::aur::AurError) -> Self {
+		return ZeusError {
+			caller: "AUR".to_string(),
+			message: e.to_string(),
+		};
+	}
+}
+
 #[macro_export]
 macro_rules! zerr {
 	($x:expr, $caller:expr, $($arg:tt)*) => {
@@ -0,0 +1,183 @@
+//! Machine readable output of query results.
+//!
+//! Everything here works on the serialized form of a record, so the
+//! field names are the same ones `--output json` shows.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::{Result, ZeusError};
+
+/// A part of a `--format` template
+#[derive(Debug, PartialEq)]
+enum Part {
+	Literal(String),
+	Field(String),
+}
+
+/// A template like `{Name}\t{Version}`
+#[derive(Debug)]
+pub struct Template {
+	parts: Vec<Part>,
+}
+
+impl Template {
+	/// Parse `s`, checking that every field exists in records of type
+	/// `T`.
+	///
+	/// `{{` and `}}` are literal braces, `\t`, `\n` and `\\` are
+	/// escapes like in expac.
+	pub fn parse<T>(s: &str) -> Result<Self>
+	where
+		T: Serialize + Default,
+	{
+		let err = |message: String| {
+			Err(ZeusError::new("format".to_owned(), message))
+		};
+
+		let known = fields(&T::default());
+
+		let mut parts = vec![];
+		let mut literal = String::new();
+		let mut chars = s.chars().peekable();
+
+		while let Some(c) = chars.next() {
+			match c {
+				'{' if chars.peek() == Some(&'{') => {
+					chars.next();
+					literal.push('{');
+				},
+				'}' if chars.peek() == Some(&'}') => {
+					chars.next();
+					literal.push('}');
+				},
+				'{' => {
+					let mut name = String::new();
+					loop {
+						match chars.next() {
+							Some('}') => break,
+							Some(c) => name.push(c),
+							None => {
+								return err(format!(
+									"Unclosed field in template: {{{}",
+									name
+								))
+							},
+						}
+					}
+
+					if !known.contains_key(&name) {
+						return err(format!(
+							"Unknown field: {}",
+							name
+						));
+					}
+
+					if !literal.is_empty() {
+						parts.push(Part::Literal(literal));
+						literal = String::new();
+					}
+					parts.push(Part::Field(name));
+				},
+				'}' => {
+					return err("Unmatched } in template".to_owned())
+				},
+				'\\' => match chars.next() {
+					Some('t') => literal.push('\t'),
+					Some('n') => literal.push('\n'),
+					Some('\\') => literal.push('\\'),
+					Some(c) => {
+						literal.push('\\');
+						literal.push(c);
+					},
+					None => literal.push('\\'),
+				},
+				c => literal.push(c),
+			}
+		}
+
+		if !literal.is_empty() {
+			parts.push(Part::Literal(literal));
+		}
+
+		Ok(Self { parts })
+	}
+
+	/// Fill in the template with the fields of `record`.
+	pub fn render<T: Serialize>(&self, record: &T) -> String {
+		let fields = fields(record);
+
+		self.parts
+			.iter()
+			.map(|x| match x {
+				Part::Literal(v) => v.clone(),
+				Part::Field(name) => {
+					fields.get(name).map(to_text).unwrap_or_default()
+				},
+			})
+			.collect()
+	}
+}
+
+/// The fields of `record`
+fn fields<T: Serialize>(record: &T) -> Map<String, Value> {
+	match serde_json::to_value(record) {
+		Ok(Value::Object(v)) => v,
+		_ => Map::new(),
+	}
+}
+
+/// A field as text, lists are separated by spaces
+fn to_text(value: &Value) -> String {
+	match value {
+		Value::Null => String::new(),
+		Value::String(v) => v.clone(),
+		// all floats are f32, don't show the noise of the f64 they
+		// were widened to
+		Value::Number(v) if v.is_f64() => {
+			(v.as_f64().unwrap_or_default() as f32).to_string()
+		},
+		Value::Array(v) => {
+			v.iter().map(to_text).collect::<Vec<_>>().join(" ")
+		},
+		v => v.to_string(),
+	}
+}
+
+/// Print `record` as `Field: value` lines, with lists as `- item`
+/// lines below their field. Empty fields are left out.
+pub fn print_plain<T: Serialize>(record: &T) {
+	for (name, value) in fields(record) {
+		match value {
+			Value::Null => {},
+			Value::Array(v) => {
+				println!("{}:", name);
+				for item in v {
+					println!("  - {}", to_text(&item));
+				}
+			},
+			v => println!("{}: {}", name, to_text(&v)),
+		}
+	}
+
+	println!();
+}
+
+/// Print the `columns` of `record` on one tab separated line.
+pub fn print_tsv<T: Serialize>(record: &T, columns: &[&str]) {
+	let fields = fields(record);
+
+	let line: Vec<String> = columns
+		.iter()
+		.map(|x| {
+			fields
+				.get(*x)
+				.map(to_text)
+				.unwrap_or_default()
+				// keep one record per line
+				.replace(['\t', '\n'], " ")
+		})
+		.collect();
+
+	println!("{}", line.join("\t"));
+}
@@ -1,4 +1,11 @@
-mod aur;
+pub mod aur;
+pub mod cli;
 mod config;
-mod error;
+pub mod error;
+pub mod format;
+pub mod log;
 pub mod machine;
+pub mod refine;
+pub mod resolve;
+pub mod scan;
+pub mod term;
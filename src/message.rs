@@ -2,10 +2,14 @@ use serde::{Deserialize, Serialize};
 
 use crate::aur::Package;
 use crate::config::AppConfig;
+use crate::db::Entry;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Message {
 	Config(AppConfig),
+	Built(Entry),
+	/// A package base and the commit it was fetched at
+	Fetched(String, String),
 	Success(Vec<Package>),
 	Failure(String),
 }
@@ -4,7 +4,16 @@ use crate::cli;
 
 use super::prelude::*;
 
-pub fn completions(args: &ArgMatches) -> Result<()> {
+pub fn completions(cfg: AppConfig, args: &ArgMatches) -> Result<()> {
+	if let Some(prefix) = args.value_of("packages") {
+		// completions should never print errors
+		for name in cfg.aur.suggest(prefix).unwrap_or_default() {
+			println!("{}", name);
+		}
+
+		return Ok(());
+	}
+
 	if args.is_present("shell") {
 		cli::make_completions(
 			args.value_of_t::<cli::Shell>("shell")
@@ -1,8 +1,10 @@
+use std::collections::BTreeMap;
 use std::env;
 use std::path::Path;
 use std::thread;
 
 use crate::config::Operation;
+use crate::db::Entry;
 use crate::lock::Lockfile;
 use crate::machine::manager::RuntimeManager;
 use crate::message::Message;
@@ -12,6 +14,7 @@ mod build;
 mod completions;
 mod query;
 mod remove;
+mod review;
 mod runtime;
 mod sync;
 
@@ -20,6 +23,8 @@ mod prelude {
 
 	pub use crate::aur::Package;
 
+	pub use crate::db::Database;
+
 	// Error handling
 	pub use crate::error::{Result, ZeusError};
 	pub use crate::zerr;
@@ -40,10 +45,21 @@ mod prelude {
 
 use prelude::*;
 
+/// What the builder reported back after an operation
+#[derive(Debug, Default)]
+pub struct BuilderReport {
+	/// Packages the operation succeeded for
+	pub packages: Vec<Package>,
+	/// Build results, one for each package the builder tried to build
+	pub builds: Vec<Entry>,
+	/// Fetched commits, keyed by package base
+	pub fetched: BTreeMap<String, String>,
+}
+
 pub fn start_builder(
 	runtime: &mut Runtime,
 	cfg: &AppConfig,
-) -> Result<Vec<Package>> {
+) -> Result<BuilderReport> {
 	if !runtime.list_machines()?.iter().any(|x| x == &cfg.machine) {
 		return Err(ZeusError::new(
 			"zeus".to_owned(),
@@ -56,7 +72,7 @@ pub fn start_builder(
 
 	let cfg1 = cfg.clone();
 	let manager_thread =
-		thread::spawn(move || -> Result<Vec<Package>> {
+		thread::spawn(move || -> Result<BuilderReport> {
 			let socket_path =
 				format!("{}/.zeus.sock", &cfg1.build_dir);
 			let listener = zerr!(
@@ -77,6 +93,8 @@ pub fn start_builder(
 
 			tx.send(Message::Config(cfg1))?;
 
+			let mut report = BuilderReport::default();
+
 			loop {
 				use std::io::ErrorKind;
 				match rx.recv() {
@@ -93,8 +111,15 @@ pub fn start_builder(
 						))
 					},
 					Ok(v) => match v {
+						Message::Built(entry) => {
+							report.builds.push(entry);
+						},
+						Message::Fetched(base, commit) => {
+							report.fetched.insert(base, commit);
+						},
 						Message::Success(pkgs) => {
-							return Ok(pkgs);
+							report.packages = pkgs;
+							return Ok(report);
 						},
 						Message::Failure(error) => {
 							return Err(ZeusError::new(
@@ -208,9 +233,18 @@ pub fn run_operation(
 			get_lock(&mut lockfile, &cfg)?;
 			runtime::runtime(term, &mut rt_manager, cfg, args)
 		},
+		Operation::Query if args.is_present("select") => {
+			get_lock(&mut lockfile, &cfg)?;
+			query::select(
+				term,
+				get_runtime(&cfg, &mut rt_manager)?,
+				cfg,
+				args,
+			)
+		},
 		Operation::Query => query::query(term, cfg, args),
-		Operation::Completions => completions::completions(args),
-		Operation::None => Err(ZeusError::new(
+		Operation::Completions => completions::completions(cfg, args),
+		Operation::Fetch | Operation::None => Err(ZeusError::new(
 			"zeus".to_owned(),
 			"No such operation".to_owned(),
 		)),
@@ -1,7 +1,132 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
 use std::io::stdout;
 
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+
+use serde::Serialize;
+
 use super::prelude::*;
-use crate::aur;
+use super::sync;
+use crate::aur::{self, vercmp, Aur, Timestamp};
+use crate::db::{BuildResult, Entry};
+use crate::format::{print_plain, print_tsv, Template};
+use crate::pacman;
+use crate::refine::Refine;
+use crate::term::parse_selection;
+
+/// Columns of `--output tsv` for AUR packages
+const TSV_COLUMNS: &[&str] = &[
+	"Name",
+	"Version",
+	"Maintainer",
+	"NumVotes",
+	"Popularity",
+	"OutOfDate",
+	"LastModified",
+	"Description",
+];
+
+/// Columns of `--output tsv` for synced packages
+const LOCAL_TSV_COLUMNS: &[&str] = &["name", "version", "result"];
+
+/// A package from the AUR and what we know about it locally
+#[derive(Debug, Default, Serialize)]
+struct QueryResult {
+	#[serde(flatten)]
+	package: Package,
+	#[serde(flatten)]
+	status: Status,
+}
+
+/// Local state of an AUR package
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct Status {
+	/// The package is in the build directory
+	synced: bool,
+	/// Version installed on the host
+	installed: Option<String>,
+	/// The synced or the installed version is older than the one in
+	/// the AUR
+	outdated: bool,
+}
+
+impl Status {
+	fn tags(&self) -> String {
+		let mut tags = vec![];
+
+		if self.synced {
+			tags.push("[synced]".cyan().to_string());
+		}
+
+		if let Some(version) = &self.installed {
+			tags.push(
+				format!("[installed {}]", version)
+					.green()
+					.to_string(),
+			);
+		}
+
+		if self.outdated {
+			tags.push("[outdated]".red().bold().to_string());
+		}
+
+		tags.join(" ")
+	}
+}
+
+/// Find out the local state of `packages`.
+fn local_status(
+	cfg: &AppConfig,
+	packages: Vec<Package>,
+) -> Vec<QueryResult> {
+	// neither is required to query the AUR
+	let db = Database::open(&cfg.build_dir).unwrap_or_default();
+	let installed = match pacman::installed_packages() {
+		Ok(v) => v,
+		Err(e) => {
+			debug!("zeus", "Cannot list installed packages: {}", e);
+			vec![]
+		},
+	};
+
+	packages
+		.into_iter()
+		.map(|package| {
+			let mut status = Status::default();
+
+			let (name, version) =
+				match (&package.Name, &package.Version) {
+					(Some(name), Some(version)) => (name, version),
+					_ => return QueryResult { package, status },
+				};
+
+			let is_older =
+				|x: &String| vercmp(x, version) == Ordering::Less;
+
+			if let Some(entry) = db.get(name) {
+				status.synced = true;
+				status.outdated |= entry
+					.version
+					.as_ref()
+					.map(is_older)
+					.unwrap_or(false);
+			}
+
+			if let Some(x) = installed
+				.iter()
+				.find(|x| x.Name.as_ref() == Some(name))
+			{
+				status.installed = x.Version.clone();
+				status.outdated |=
+					x.Version.as_ref().map(is_older).unwrap_or(false);
+			}
+
+			QueryResult { package, status }
+		})
+		.collect()
+}
 
 macro_rules! print_if_some {
 	($a:expr,$b:expr) => {{
@@ -25,12 +150,70 @@ macro_rules! print_vec_if_some {
 	}};
 }
 
-fn print_pretty_package(package: &aur::Package) {
+/// Format `timestamp` as a date, either in local time or as
+/// ISO-8601 in UTC.
+fn format_date(timestamp: Timestamp, iso: bool) -> String {
+	let date = match Utc.timestamp_opt(timestamp as i64, 0).single() {
+		Some(v) => v,
+		None => return timestamp.to_string(),
+	};
+
+	match iso {
+		true => date.to_rfc3339_opts(SecondsFormat::Secs, true),
+		false => DateTime::<Local>::from(date)
+			.format("%a %d %b %Y %H:%M:%S")
+			.to_string(),
+	}
+}
+
+/// How long ago `timestamp` was, like `3 months ago`.
+fn format_age(timestamp: Timestamp) -> String {
+	let secs = (Utc::now().timestamp() - timestamp as i64).max(0);
+
+	let (amount, unit) = match secs {
+		0..=59 => return "just now".to_owned(),
+		60..=3599 => (secs / 60, "minute"),
+		3600..=86399 => (secs / 3600, "hour"),
+		86400..=604799 => (secs / 86400, "day"),
+		604800..=2591999 => (secs / 604800, "week"),
+		2592000..=31535999 => (secs / 2592000, "month"),
+		_ => (secs / 31536000, "year"),
+	};
+
+	match amount {
+		1 => format!("1 {} ago", unit),
+		_ => format!("{} {}s ago", amount, unit),
+	}
+}
+
+fn print_date(name: &str, timestamp: Option<Timestamp>, iso: bool) {
+	if let Some(v) = timestamp {
+		println!(
+			"{0: <16}: {1} ({2})",
+			name,
+			format_date(v, iso),
+			format_age(v)
+		);
+	}
+}
+
+fn print_pretty_package(aur: &Aur, result: &QueryResult, iso: bool) {
+	let QueryResult { package, status } = result;
+
 	print_if_some!("Name", &package.Name);
 	print_if_some!("Version", &package.Version);
 	print_if_some!("Description", &package.Description);
 	print_if_some!("URL", &package.URL);
 
+	if let Some(name) = &package.Name {
+		println!(
+			"{0: <16}: {1}packages/{2}",
+			"AUR URL",
+			aur.get_url(),
+			name
+		);
+	}
+
 	print_vec_if_some!("License", &package.License);
 	print_vec_if_some!("Groups", &package.Groups);
 	print_vec_if_some!("Provides", &package.Provides);
@@ -45,17 +228,139 @@ fn print_pretty_package(package: &aur::Package) {
 		&package.Maintainer.as_ref().unwrap_or(&"none".to_owned())
 	);
 
-	print_if_some!("Last Modified", &package.LastModified);
-	print_if_some!("First Submitted", &package.FirstSubmitted);
+	print_date("Last Modified", package.LastModified, iso);
+	print_date("First Submitted", package.FirstSubmitted, iso);
 
-	println!(
-		"{0: <16}: {1}",
-		"Out of date",
-		&package.OutOfDate.unwrap_or(0)
-	);
+	match package.OutOfDate {
+		Some(v) => println!(
+			"{0: <16}: {1}",
+			"Out of date",
+			format!(
+				"Flagged out-of-date since {} ({})",
+				format_date(v, iso),
+				format_age(v)
+			)
+			.red()
+			.bold()
+		),
+		None => println!("{0: <16}: No", "Out of date"),
+	}
 
 	print_if_some!("Popularity", &package.Popularity);
 	print_if_some!("Votes", &package.NumVotes);
+
+	let tags = status.tags();
+	if !tags.is_empty() {
+		println!("{0: <16}: {1}", "Local Status", tags);
+	}
+}
+
+/// The filters, sorting and limit of `args`
+fn refine(args: &ArgMatches) -> Refine {
+	// all values are validated in cli.rs
+	Refine {
+		min_votes: args.value_of_t("min-votes").ok(),
+		maintained_only: args.is_present("maintained-only"),
+		not_out_of_date: args.is_present("not-out-of-date"),
+		sort: args.value_of_t("sort").ok(),
+		reverse: args.is_present("reverse"),
+		limit: args.value_of_t("limit").ok(),
+	}
+}
+
+fn query_local(cfg: &AppConfig, args: &ArgMatches) -> Result<()> {
+	let db = zerr!(
+		Database::open(&cfg.build_dir),
+		"db",
+		"Cannot open package database"
+	);
+
+	let entries: Vec<&Entry> = db
+		.packages
+		.values()
+		.filter(|x| {
+			cfg.keywords.is_empty()
+				|| cfg.keywords.iter().any(|k| x.name.contains(k))
+		})
+		.collect();
+
+	match args.value_of("output").unwrap() {
+		"json" => zerr!(
+			serde_json::to_writer(stdout(), &entries),
+			"zeus",
+			"Cannot serialize JSON: "
+		),
+		"plain" => entries.iter().for_each(print_plain),
+		"tsv" => entries
+			.iter()
+			.for_each(|x| print_tsv(x, LOCAL_TSV_COLUMNS)),
+		_ => {
+			for entry in entries {
+				println!(
+					"{} {} {}",
+					entry.name.bold(),
+					entry
+						.version
+						.as_ref()
+						.unwrap_or(&"unknown".to_owned())
+						.bright_blue(),
+					match entry.result {
+						BuildResult::Failed => "(build failed)".red(),
+						_ => "".normal(),
+					}
+				);
+			}
+		},
+	}
+
+	Ok(())
+}
+
+/// Search for or get info about the packages in `cfg.keywords`,
+/// as `args` says.
+fn find_packages(
+	cfg: &AppConfig,
+	args: &ArgMatches,
+) -> Result<Vec<QueryResult>> {
+	if cfg.keywords.is_empty() {
+		return Err(ZeusError::new(
+			"zeus".to_owned(),
+			"No keywords specified".to_owned(),
+		));
+	}
+
+	let by = args.value_of_t::<aur::By>("by").unwrap();
+
+	let mut data = match args.is_present("info") {
+		true => cfg.aur.info(&cfg.keywords),
+		false => cfg.aur.search(by, &cfg.keywords),
+	}?;
+
+	refine(args).apply(&mut data.results);
+
+	Ok(local_status(cfg, data.results))
+}
+
+fn print_search_result<T: Display>(marker: T, result: &QueryResult) {
+	let QueryResult { package, status } = result;
+
+	let mut tags = status.tags();
+	if !tags.is_empty() {
+		tags.insert(0, ' ');
+	}
+
+	println!(
+		"{} {} - {}{}\n    {}",
+		marker,
+		package.Name.as_ref().unwrap_or(&"".to_owned()).bold(),
+		package
+			.Version
+			.as_ref()
+			.unwrap_or(&"".to_owned())
+			.bright_blue(),
+		tags,
+		package.Description.as_ref().unwrap_or(&"".to_owned()),
+	);
 }
 
 pub fn query(
@@ -69,53 +374,42 @@ pub fn query(
 		.map(|x| x.to_owned())
 		.collect();
 
-	if cfg.keywords.is_empty() {
-		return Err(ZeusError::new(
-			"zeus".to_owned(),
-			"No keywords specified".to_owned(),
-		));
+	if args.is_present("local") {
+		return query_local(&cfg, args);
 	}
 
-	let by = args.value_of_t::<aur::By>("by").unwrap();
+	let results = find_packages(&cfg, args)?;
 
-	let res = match args.is_present("info") {
-		true => cfg.aur.info(&cfg.keywords),
-		false => cfg.aur.search(by, &cfg.keywords),
-	};
+	if let Some(format) = args.value_of("format") {
+		let template = Template::parse::<QueryResult>(format)?;
 
-	let data = zerr!(res, "aur", "Error: ");
+		for result in &results {
+			println!("{}", template.render(result));
+		}
+
+		return Ok(());
+	}
 
 	match args.value_of("output").unwrap() {
 		"json" => zerr!(
-			serde_json::to_writer(stdout(), &data.results),
+			serde_json::to_writer(stdout(), &results),
 			"zeus",
 			"Cannot serialize JSON: "
 		),
+		"plain" => results.iter().for_each(print_plain),
+		"tsv" => {
+			results.iter().for_each(|x| print_tsv(x, TSV_COLUMNS))
+		},
 		_ => {
 			if args.is_present("info") {
-				for package in &data.results {
-					print_pretty_package(package);
+				let iso = args.value_of("date-format") == Some("iso");
+
+				for result in &results {
+					print_pretty_package(&cfg.aur, result, iso);
 				}
 			} else {
-				for package in &data.results {
-					println!(
-						"{} {} - {}\n    {}",
-						"=>".green(),
-						package
-							.Name
-							.as_ref()
-							.unwrap_or(&"".to_owned())
-							.bold(),
-						package
-							.Version
-							.as_ref()
-							.unwrap_or(&"".to_owned())
-							.bright_blue(),
-						package
-							.Description
-							.as_ref()
-							.unwrap_or(&"".to_owned()),
-					);
+				for result in &results {
+					print_search_result("=>".green(), result);
 				}
 			}
 		},
@@ -123,3 +417,59 @@ pub fn query(
 
 	Ok(())
 }
+
+/// Search for `keywords` and sync the packages the user picks.
+pub fn select(
+	term: &mut Terminal,
+	runtime: &mut Runtime,
+	mut cfg: AppConfig,
+	args: &ArgMatches,
+) -> Result<()> {
+	cfg.keywords = args
+		.values_of("keywords")
+		.unwrap_or_default()
+		.map(|x| x.to_owned())
+		.collect();
+
+	let results = find_packages(&cfg, args)?;
+
+	if results.is_empty() {
+		return Err(ZeusError::new(
+			"zeus".to_owned(),
+			"No packages found.".to_owned(),
+		));
+	}
+
+	for (index, result) in results.iter().enumerate() {
+		print_search_result(
+			(index + 1).to_string().yellow().bold(),
+			result,
+		);
+	}
+
+	let input = zerr!(
+		term.prompt("Packages to sync (eg: 1 2 3, 1-3 or ^4)"),
+		"zeus",
+		"Cannot read selection"
+	);
+
+	let selected = parse_selection(&input, results.len());
+
+	if selected.is_empty() {
+		error!("zeus", "Aborting...");
+		return Ok(());
+	}
+
+	cfg.packages = results
+		.into_iter()
+		.enumerate()
+		.filter(|(index, _)| selected.contains(index))
+		.map(|(_, x)| x.package)
+		.collect();
+
+	cfg.machine = args.value_of("name").unwrap().to_owned();
+	cfg.review = true;
+	cfg.install = true;
+
+	sync::sync_packages(term, runtime, cfg)
+}
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::collections::HashSet;
 
 use super::prelude::*;
 
@@ -12,30 +12,41 @@ pub fn remove(
 
 	cfg.machine = args.value_of("name").unwrap().to_owned();
 
-	cfg.packages = args
+	let mut db = zerr!(
+		Database::open(&cfg.build_dir),
+		"db",
+		"Cannot open package database"
+	);
+
+	let requested: Vec<&str> = args
 		.values_of("packages")
 		.unwrap_or_default()
-		.map(|x| Package {
-			Name: Some(x.to_owned()),
-			..Default::default()
-		})
-		.filter(|x| {
-			let pkg_path = Path::new(&cfg.build_dir)
-				.join(x.Name.as_ref().unwrap());
-
-			if pkg_path.exists()
-				&& pkg_path.is_dir()
-				&& pkg_path.join("PKGBUILD").exists()
-			{
-				true
-			} else {
-				warning!(
-					"zeus",
-					"Package {} was not found",
-					x.Name.as_ref().unwrap()
-				);
+		.filter(|x| match db.get(x) {
+			Some(_) => true,
+			None => {
+				warning!("zeus", "Package {} was not found", x);
 				false
-			}
+			},
+		})
+		.collect();
+
+	let bases: HashSet<String> = requested
+		.iter()
+		.filter_map(|x| db.get(x))
+		.map(|x| x.base().to_owned())
+		.collect();
+
+	// split packages share the directory of their base, so they can
+	// only be removed together
+	cfg.packages = db
+		.packages
+		.values()
+		.filter(|x| bases.contains(x.base()))
+		.map(|entry| Package {
+			Name: Some(entry.name.clone()),
+			PackageBase: entry.base.clone(),
+			Version: entry.version.clone(),
+			..Default::default()
 		})
 		.collect();
 
@@ -46,15 +57,40 @@ pub fn remove(
 		));
 	}
 
+	let siblings: Vec<&String> = cfg
+		.packages
+		.iter()
+		.filter_map(|x| x.Name.as_ref())
+		.filter(|x| !requested.contains(&x.as_str()))
+		.collect();
+
 	term.list(
 		format!(
 			"The following packages will be {}:",
 			"REMOVED".bold()
 		),
-		cfg.packages.iter().filter_map(|x| x.Name.as_ref()),
+		requested.iter(),
 		4,
 	)?;
 
+	if !siblings.is_empty() {
+		term.list(
+			format!(
+				"The following packages share a base with them and will also be {} from the build directory:",
+				"DROPPED".bold()
+			),
+			siblings.iter(),
+			4,
+		)?;
+
+		if cfg.uninstall {
+			warning!(
+				"zeus",
+				"Packages that share a base with them stay installed"
+			);
+		}
+	}
+
 	if !term.yes_no_question(
 		"Are you sure you want to remove these packages?",
 		true,
@@ -63,18 +99,26 @@ pub fn remove(
 		return Ok(());
 	}
 
-	let removed_packages = start_builder(runtime, &cfg)?;
+	let removed_packages = start_builder(runtime, &cfg)?.packages;
+
+	for package in &removed_packages {
+		if let Some(name) = &package.Name {
+			db.remove(name);
+		}
+	}
+
+	zerr!(db.save(), "db", "Cannot save package database");
 
 	if cfg.uninstall {
 		use std::process::Command;
 
-		let mut packages = vec![];
-
-		for p in &removed_packages {
-			if let Some(pkg) = &p.Name {
-				packages.push(pkg);
-			}
-		}
+		// the other packages of a base were not asked for, so
+		// they stay installed
+		let packages: Vec<&String> = removed_packages
+			.iter()
+			.filter_map(|x| x.Name.as_ref())
+			.filter(|x| requested.contains(&x.as_str()))
+			.collect();
 
 		zerr!(
 			Command::new("sudo")
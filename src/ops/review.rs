@@ -0,0 +1,227 @@
+//! Review of packages before they are built.
+//!
+//! Packages are cloned or fetched by the builder without being
+//! built, and the host only reads them, so the user can see what
+//! changed in the files that run code before any of it runs. The
+//! builder owns the checkouts, so the host never writes to them.
+//! Reviewed commits are kept in the database, so only new changes are
+//! shown.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use super::prelude::*;
+
+use crate::config::Operation;
+
+/// The tree with no files, to show new packages in full
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// Files that are shown in the review
+const REVIEWED_FILES: &[&str] = &["PKGBUILD", "*.install"];
+
+/// Git in `dir`, which belongs to the builder and not to the user.
+fn git_command(dir: &Path) -> Command {
+	let mut command = Command::new("git");
+
+	command
+		.arg("-c")
+		.arg(format!("safe.directory={}", dir.display()))
+		.arg("-C")
+		.arg(dir);
+
+	command
+}
+
+/// Run git in `dir` and return its output.
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+	let output = zerr!(
+		git_command(dir).args(args).output(),
+		"git",
+		"Cannot execute git"
+	);
+
+	if !output.status.success() {
+		return Err(ZeusError::new(
+			"git".to_owned(),
+			format!(
+				"git {} failed: {}",
+				args.first().unwrap_or(&""),
+				String::from_utf8_lossy(&output.stderr).trim()
+			),
+		));
+	}
+
+	Ok(String::from_utf8_lossy(&output.stdout)
+		.trim_end()
+		.to_owned())
+}
+
+/// Have the builder clone or fetch the package bases of
+/// `cfg.packages` without building them.
+///
+/// Returns the commit each base will be built at.
+pub fn fetch(
+	runtime: &mut Runtime,
+	cfg: &AppConfig,
+) -> Result<BTreeMap<String, String>> {
+	let mut cfg = cfg.clone();
+	cfg.operation = Operation::Fetch;
+
+	Ok(start_builder(runtime, &cfg)?.fetched)
+}
+
+/// Read the reviewed files of the package base `base` at the commit
+/// in `cfg.commits` it will be built at.
+///
+/// Returns the name and the contents of each file.
+pub fn scripts(
+	cfg: &AppConfig,
+	base: &str,
+) -> Result<Vec<(String, String)>> {
+	let commit = cfg.commits.get(base).ok_or_else(|| {
+		ZeusError::new(
+			"zeus".to_owned(),
+			"it was not fetched".to_owned(),
+		)
+	})?;
+
+	let dir = Path::new(&cfg.build_dir).join(base);
+
+	git(&dir, &["ls-tree", "--name-only", commit])?
+		.lines()
+		.filter(|x| *x == "PKGBUILD" || x.ends_with(".install"))
+		.map(|file| {
+			let script = git(
+				&dir,
+				&["show", &format!("{}:{}", commit, file)],
+			)?;
+
+			Ok((file.to_owned(), script))
+		})
+		.collect()
+}
+
+/// Show the changes of the reviewed files in `old..new` through the
+/// pager.
+fn show_diff(dir: &Path, old: &str, new: &str) -> Result<()> {
+	let mut command = git_command(dir);
+
+	command
+		.args(["-p", "diff", &format!("{}..{}", old, new), "--"])
+		.args(REVIEWED_FILES);
+
+	// git prefers its own pager settings
+	if let Ok(pager) = env::var("PAGER") {
+		command.env("GIT_PAGER", pager);
+	}
+
+	let status = zerr!(command.status(), "git", "Cannot execute git");
+
+	if !status.success() {
+		return Err(ZeusError::new(
+			"git".to_owned(),
+			format!(
+				"git diff failed with: {}",
+				status.code().unwrap_or(-1)
+			),
+		));
+	}
+
+	Ok(())
+}
+
+/// Show what changed in `cfg.packages` since they were last reviewed
+/// and ask which ones to build. Packages whose reviewed commit is
+/// still the latest are built without asking.
+///
+/// Split packages are reviewed once for their base, at the commit
+/// in `cfg.commits` the builder fetched and will check out.
+pub fn review(
+	term: &mut Terminal,
+	cfg: &AppConfig,
+	db: &mut Database,
+) -> Result<Vec<Package>> {
+	let mut bases: Vec<&str> = vec![];
+	for base in cfg.packages.iter().filter_map(|x| x.base()) {
+		if !bases.contains(&base) {
+			bases.push(base);
+		}
+	}
+
+	let mut accepted = vec![];
+
+	for base in bases {
+		let commit = match cfg.commits.get(base) {
+			Some(v) => v.to_owned(),
+			None => {
+				warning!(
+					"zeus",
+					"Skipping {}, cannot fetch it",
+					base
+				);
+				continue;
+			},
+		};
+
+		let dir = Path::new(&cfg.build_dir).join(base);
+
+		if db.reviewed(base) == Some(commit.as_str()) {
+			debug!("zeus", "{} has already been reviewed", base);
+		} else {
+			info!("zeus", "Reviewing {}", base.bold());
+
+			// packages synced before reviews existed are compared
+			// with the last build, and either commit might be gone
+			// after a force push
+			let old = db
+				.reviewed(base)
+				.or_else(|| {
+					db.by_base(base).find_map(|x| x.commit.as_deref())
+				})
+				.filter(|x| {
+					git(
+						&dir,
+						&[
+							"cat-file",
+							"-e",
+							&format!("{}^{{commit}}", x),
+						],
+					)
+					.is_ok()
+				})
+				.unwrap_or(EMPTY_TREE)
+				.to_owned();
+
+			match old == commit {
+				true => {
+					info!("zeus", "No changes since the last build")
+				},
+				false => show_diff(&dir, &old, &commit)?,
+			}
+
+			if !term.yes_no_question(
+				format!("Build {}?", base.bold()),
+				true,
+			)? {
+				info!("zeus", "Skipping {}", base);
+				continue;
+			}
+
+			db.set_reviewed(base, &commit);
+		}
+
+		accepted.push(base);
+	}
+
+	Ok(cfg
+		.packages
+		.iter()
+		.filter(|x| {
+			x.base().map(|x| accepted.contains(&x)).unwrap_or(false)
+		})
+		.cloned()
+		.collect())
+}
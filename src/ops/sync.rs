@@ -1,8 +1,146 @@
-use std::fs;
+use std::cmp::Ordering;
+use std::collections::HashSet;
 
 use super::prelude::*;
+use super::review;
 use super::start_builder;
 
+use crate::aur::{vercmp, DepSpec};
+use crate::db::BuildResult;
+use crate::pacman;
+use crate::resolve;
+use crate::scan::{self, Finding};
+
+/// Find the synced packages which have a newer version in the AUR.
+fn outdated_packages(
+	cfg: &AppConfig,
+	db: &Database,
+) -> Result<Vec<Package>> {
+	let names: Vec<&String> = db.packages.keys().collect();
+
+	let packages = zerr!(
+		cfg.aur.info(&names),
+		"AUR",
+		"Cannot request info for packages"
+	)
+	.results;
+
+	let mut outdated = vec![];
+	for package in packages {
+		let (name, new) = match (&package.Name, &package.Version) {
+			(Some(name), Some(version)) => (name, version),
+			_ => continue,
+		};
+
+		let old = match db.get(name).and_then(|x| x.version.as_ref())
+		{
+			Some(v) => v,
+			// there is no way to know, so just rebuild it
+			None => {
+				outdated.push(package);
+				continue;
+			},
+		};
+
+		if vercmp(old, new) == Ordering::Less {
+			outdated.push(package);
+		} else {
+			debug!("zeus", "Package {} is up to date", name);
+		}
+	}
+
+	Ok(outdated)
+}
+
+/// Find conflicts between `packages` and with `installed`.
+///
+/// Returns the package, the package it conflicts with and the
+/// entry in `Conflicts` that matched.
+fn find_conflicts(
+	packages: &[Package],
+	installed: &[Package],
+) -> Vec<(String, String, DepSpec)> {
+	let name = |x: &Package| x.Name.clone().unwrap_or_default();
+
+	// installed packages that are about to be replaced do not count
+	let installed: Vec<&Package> = installed
+		.iter()
+		.filter(|x| !packages.iter().any(|p| p.Name == x.Name))
+		.collect();
+
+	let mut conflicts = vec![];
+
+	for a in packages {
+		let others = packages
+			.iter()
+			.filter(|x| x.Name != a.Name)
+			.chain(installed.iter().copied());
+
+		for b in others {
+			for spec in
+				a.Conflicts.iter().flatten().map(DepSpec::from)
+			{
+				if b.satisfies(&spec) {
+					conflicts.push((name(a), name(b), spec));
+				}
+			}
+		}
+	}
+
+	for a in installed {
+		for spec in a.Conflicts.iter().flatten().map(DepSpec::from) {
+			for b in packages {
+				if b.satisfies(&spec) {
+					conflicts.push((name(a), name(b), spec.clone()));
+				}
+			}
+		}
+	}
+
+	conflicts
+}
+
+/// Scan the PKGBUILDs and install scripts of `cfg.packages` at the
+/// commits in `cfg.commits` they will be built at.
+///
+/// Returns the package base, the file and what was found in it, and
+/// the package bases that could not be scanned.
+fn find_risks(
+	cfg: &AppConfig,
+) -> (Vec<(String, String, Finding)>, Vec<String>) {
+	let mut risks = vec![];
+	let mut unscanned = vec![];
+
+	let mut bases: Vec<&str> =
+		cfg.packages.iter().filter_map(|x| x.base()).collect();
+	bases.sort_unstable();
+	bases.dedup();
+
+	for name in bases {
+		let scripts = match review::scripts(cfg, name) {
+			Ok(v) => v,
+			Err(e) => {
+				warning!(
+					"zeus",
+					"Cannot scan {}: {}",
+					name,
+					e.message
+				);
+				unscanned.push(name.to_owned());
+				continue;
+			},
+		};
+
+		for (file, script) in scripts {
+			for finding in scan::scan(&script) {
+				risks.push((name.to_owned(), file.clone(), finding));
+			}
+		}
+	}
+
+	(risks, unscanned)
+}
+
 pub fn sync(
 	term: &mut Terminal,
 	runtime: &mut Runtime,
@@ -11,6 +149,7 @@ pub fn sync(
 ) -> Result<()> {
 	cfg.upgrade = args.is_present("upgrade");
 	cfg.install = args.is_present("install");
+	cfg.review = !args.is_present("no-review");
 
 	cfg.build_args = args
 		.value_of("buildargs")
@@ -30,45 +169,30 @@ pub fn sync(
 		})
 		.collect();
 
+	sync_packages(term, runtime, cfg)
+}
+
+/// Resolve, build and optionally install `cfg.packages`, or all
+/// outdated packages if there are none and `cfg.upgrade` is set.
+pub fn sync_packages(
+	term: &mut Terminal,
+	runtime: &mut Runtime,
+	mut cfg: AppConfig,
+) -> Result<()> {
+	let mut db = zerr!(
+		Database::open(&cfg.build_dir),
+		"db",
+		"Cannot open package database"
+	);
+
 	if cfg.packages.is_empty() && cfg.upgrade {
 		// CHANGELOG: Simplify code
 
-		let dir = zerr!(
-			fs::read_dir(&cfg.build_dir),
-			"fs",
-			"Cannot list {}",
-			&cfg.build_dir
-		);
+		cfg.packages = outdated_packages(&cfg, &db)?;
 
-		for entry in dir {
-			let entry = match entry {
-				Err(e) => {
-					warning!(
-						"fs",
-						"Cannot read package directory: {}",
-						e
-					);
-					continue;
-				},
-				Ok(v) => v,
-			};
-
-			if entry.path().is_dir() {
-				match entry.file_name().into_string() {
-					Ok(v) => cfg.packages.push(Package {
-						Name: Some(v),
-						..Default::default()
-					}),
-					Err(e) => {
-						warning!(
-							"fs",
-							"Found invalid package directory: {}",
-							e.to_string_lossy()
-						);
-						continue;
-					},
-				};
-			}
+		if cfg.packages.is_empty() {
+			info!("zeus", "All packages are up to date");
+			return Ok(());
 		}
 
 		// CHANGELOG: dont ask what to upgrade
@@ -83,17 +207,26 @@ pub fn sync(
 
 	// CHANGELOG: remove invalid packages
 
-	cfg.packages = zerr!(
-		cfg.aur.info(
-			&cfg.packages
-				.iter()
-				.filter_map(|x| x.Name.as_ref())
-				.collect()
-		),
-		"AUR",
-		"Cannot request info for packages"
-	)
-	.results;
+	let repo_packages = match pacman::repo_packages() {
+		Ok(v) => v,
+		Err(e) => {
+			warning!(
+				"zeus",
+				"Cannot list repository packages: {}",
+				e
+			);
+			HashSet::new()
+		},
+	};
+
+	cfg.packages = resolve::resolve(
+		&cfg.aur,
+		&cfg.packages
+			.iter()
+			.filter_map(|x| x.Name.clone())
+			.collect::<Vec<_>>(),
+		|x| repo_packages.contains(x),
+	)?;
 
 	if cfg.packages.is_empty() {
 		return Err(ZeusError::new(
@@ -102,6 +235,34 @@ pub fn sync(
 		));
 	}
 
+	let installed = match cfg.install {
+		true => pacman::installed_packages().unwrap_or_else(|e| {
+			warning!("zeus", "Cannot list installed packages: {}", e);
+			vec![]
+		}),
+		false => vec![],
+	};
+
+	let conflicts = find_conflicts(&cfg.packages, &installed);
+
+	for (package, with, spec) in &conflicts {
+		warning!(
+			"zeus",
+			"{} conflicts with {} ({})",
+			package.bold(),
+			with.bold(),
+			spec
+		);
+	}
+
+	if !conflicts.is_empty() && !cfg.force {
+		return Err(ZeusError::new(
+			"zeus".to_owned(),
+			"Conflicting packages found. Use --force to continue anyway."
+				.to_owned(),
+		));
+	}
+
 	term.list(
 		format!(
 			"The following packages will be {}:",
@@ -111,8 +272,29 @@ pub fn sync(
 			}
 			.bold()
 		),
-		cfg.packages.iter().filter_map(|x| x.Name.as_ref()),
-		4,
+		cfg.packages.iter().filter_map(|x| {
+			let name = x.Name.as_ref()?;
+
+			match (db.get(name), &x.Version) {
+				(Some(entry), Some(new)) if cfg.upgrade => {
+					Some(format!(
+						"{} {} -> {}",
+						name,
+						entry
+							.version
+							.as_ref()
+							.unwrap_or(&"unknown".to_owned())
+							.red(),
+						new.green()
+					))
+				},
+				_ => Some(name.to_owned()),
+			}
+		}),
+		match cfg.upgrade {
+			true => 1,
+			false => 4,
+		},
 	)?;
 
 	if !term.yes_no_question(
@@ -123,33 +305,71 @@ pub fn sync(
 		return Ok(());
 	}
 
-	let mut synced_packages = start_builder(runtime, &cfg)?;
+	// the builder checks out the commits that are reviewed and scanned
+	cfg.commits = review::fetch(runtime, &cfg)?;
+
+	if cfg.review {
+		cfg.packages = review::review(term, &cfg, &mut db)?;
+		zerr!(db.save(), "db", "Cannot save package database");
+
+		if cfg.packages.is_empty() {
+			info!("zeus", "Nothing left to sync");
+			return Ok(());
+		}
+	}
+
+	let (risks, unscanned) = find_risks(&cfg);
+
+	for (package, file, finding) in &risks {
+		warning!(
+			"zeus",
+			"{}/{}:{}: {}: {}",
+			package.bold(),
+			file,
+			finding.line,
+			finding.risk,
+			finding.text
+		);
+	}
+
+	// missing checksums are only warned about
+	if risks.iter().any(|(_, _, x)| x.risk.blocks()) && !cfg.force {
+		return Err(ZeusError::new(
+			"zeus".to_owned(),
+			"Risky packages found. Use --force to continue anyway."
+				.to_owned(),
+		));
+	}
+
+	if !unscanned.is_empty() && !cfg.force {
+		return Err(ZeusError::new(
+			"zeus".to_owned(),
+			"Unscanned packages found. Use --force to continue anyway."
+				.to_owned(),
+		));
+	}
+
+	let report = start_builder(runtime, &cfg)?;
+
+	for entry in &report.builds {
+		db.record(entry.clone());
+	}
+
+	zerr!(db.save(), "db", "Cannot save package database");
 
 	if cfg.install {
 		use std::process::Command;
 
-		let mut package_files: Vec<String> = vec![];
-
-		for p in &mut synced_packages {
-			if let Some(pkg_files) = &mut p.package_files {
-				package_files.append(
-					&mut pkg_files
-						.iter()
-						.filter_map(|x| {
-							if let Some(s) = x.strip_prefix("/build")
-							{
-								Some(format!(
-									"{}/{}",
-									&cfg.build_dir, s
-								))
-							} else {
-								None
-							}
-						})
-						.collect(),
-				);
-			}
-		}
+		let package_files: Vec<String> = report
+			.builds
+			.iter()
+			.filter(|x| x.result == BuildResult::Built)
+			.flat_map(|x| &x.files)
+			.filter_map(|x| {
+				x.strip_prefix("/build")
+					.map(|s| format!("{}/{}", &cfg.build_dir, s))
+			})
+			.collect();
 
 		if package_files.is_empty() {
 			info!("zeus", "Nothing to install");
@@ -167,7 +387,7 @@ pub fn sync(
 	} else {
 		term.list(
 			"Synced packages:",
-			synced_packages.iter().filter_map(|x| x.Name.as_ref()),
+			report.packages.iter().filter_map(|x| x.Name.as_ref()),
 			1,
 		)?;
 	}
@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+use std::process::Command;
+
+use crate::aur::Package;
+use crate::error::{Result, ZeusError};
+use crate::zerr;
+
+fn run_pacman(args: &[&str]) -> Result<String> {
+	let output = zerr!(
+		Command::new("pacman")
+			.env("LC_ALL", "C")
+			.args(args)
+			.output(),
+		"pacman",
+		"Cannot execute pacman"
+	);
+
+	if !output.status.success() {
+		return Err(ZeusError::new(
+			"pacman".to_owned(),
+			format!(
+				"pacman failed with: {}",
+				output.status.code().unwrap_or(-1)
+			),
+		));
+	}
+
+	Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Get the names of all packages in the host's sync repositories.
+pub fn repo_packages() -> Result<HashSet<String>> {
+	Ok(run_pacman(&["-S", "-l", "-q"])?
+		.lines()
+		.map(|x| x.to_owned())
+		.collect())
+}
+
+/// Get all packages installed on the host.
+///
+/// Only `Name`, `Version`, `Provides` and `Conflicts` are filled in.
+pub fn installed_packages() -> Result<Vec<Package>> {
+	let output = run_pacman(&["-Q", "-i"])?;
+
+	let mut packages: Vec<Package> = vec![];
+	let mut package = Package::default();
+	let mut key = String::new();
+
+	for line in output.lines() {
+		if line.trim().is_empty() {
+			if package.Name.is_some() {
+				packages.push(package);
+			}
+			package = Package::default();
+			continue;
+		}
+
+		// long values continue on the next lines
+		let value = match line.starts_with(char::is_whitespace) {
+			true => line,
+			false => match line.split_once(':') {
+				Some((k, v)) => {
+					key = k.trim().to_owned();
+					v
+				},
+				None => continue,
+			},
+		};
+
+		let values = value
+			.split_whitespace()
+			.filter(|x| *x != "None")
+			.map(|x| x.to_owned());
+
+		match key.as_str() {
+			"Name" => package.Name = Some(value.trim().to_owned()),
+			"Version" => {
+				package.Version = Some(value.trim().to_owned())
+			},
+			"Provides" => {
+				package.Provides.get_or_insert(vec![]).extend(values)
+			},
+			"Conflicts With" => {
+				package.Conflicts.get_or_insert(vec![]).extend(values)
+			},
+			_ => {},
+		}
+	}
+
+	if package.Name.is_some() {
+		packages.push(package);
+	}
+
+	Ok(packages)
+}
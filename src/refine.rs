@@ -0,0 +1,103 @@
+//! Filters, sorting and limits for AUR query results.
+
+use std::str::FromStr;
+
+use crate::aur::Package;
+
+/// What results are sorted by, the most relevant first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+	Votes,
+	Popularity,
+	Modified,
+	Name,
+}
+
+impl FromStr for SortBy {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"votes" => Ok(Self::Votes),
+			"popularity" => Ok(Self::Popularity),
+			"modified" => Ok(Self::Modified),
+			"name" => Ok(Self::Name),
+			_ => Err(format!("Unknown sort key: {}", s)),
+		}
+	}
+}
+
+/// How query results are refined
+///
+/// # Example:
+/// ```
+/// use zeus::aur::Package;
+/// use zeus::refine::{Refine, SortBy};
+///
+/// let mut packages = vec![Package::default(); 3];
+///
+/// Refine {
+///     sort: Some(SortBy::Votes),
+///     limit: Some(2),
+///     ..Default::default()
+/// }
+/// .apply(&mut packages);
+///
+/// assert_eq!(packages.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Refine {
+	/// Hide packages with fewer votes
+	pub min_votes: Option<u64>,
+	/// Hide orphaned packages
+	pub maintained_only: bool,
+	/// Hide packages flagged out of date
+	pub not_out_of_date: bool,
+	pub sort: Option<SortBy>,
+	/// Reverse the order, sorted or not
+	pub reverse: bool,
+	/// Keep at most this many, after sorting
+	pub limit: Option<usize>,
+}
+
+impl Refine {
+	/// Filter, sort and cut `packages`.
+	pub fn apply(&self, packages: &mut Vec<Package>) {
+		packages.retain(|x| {
+			if self.maintained_only && x.Maintainer.is_none() {
+				return false;
+			}
+
+			if self.not_out_of_date && x.OutOfDate.is_some() {
+				return false;
+			}
+
+			match self.min_votes {
+				Some(min) => x.NumVotes.unwrap_or(0) >= min,
+				None => true,
+			}
+		});
+
+		if let Some(sort) = self.sort {
+			packages.sort_by(|a, b| match sort {
+				SortBy::Votes => b.NumVotes.cmp(&a.NumVotes),
+				SortBy::Popularity => b
+					.Popularity
+					.unwrap_or(0.0)
+					.total_cmp(&a.Popularity.unwrap_or(0.0)),
+				SortBy::Modified => {
+					b.LastModified.cmp(&a.LastModified)
+				},
+				SortBy::Name => a.Name.cmp(&b.Name),
+			});
+		}
+
+		if self.reverse {
+			packages.reverse();
+		}
+
+		if let Some(limit) = self.limit {
+			packages.truncate(limit);
+		}
+	}
+}
@@ -0,0 +1,191 @@
+//! Dependency resolution for AUR packages.
+//!
+//! `makepkg -s` can only install dependencies from the repositories,
+//! so any dependency that lives in the AUR has to be built before
+//! the packages that need it. The resolver walks the dependency
+//! tree through the AUR RPC interface and returns every AUR package
+//! that needs building in an order where dependencies come first.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::aur::{Aur, DepSpec, Package};
+use crate::error::{Result, ZeusError};
+use crate::{debug, warning, zerr};
+
+struct Resolver<'a, F> {
+	aur: &'a Aur,
+	is_repo: F,
+
+	/// AUR packages found so far, keyed by name
+	packages: HashMap<String, Package>,
+	/// Names the AUR has already been asked about
+	queried: HashSet<String>,
+}
+
+impl<'a, F> Resolver<'a, F>
+where
+	F: Fn(&str) -> bool,
+{
+	/// Query the AUR for `names` and every AUR dependency they
+	/// pull in, until no new names are found.
+	fn fetch(&mut self, names: &[String]) -> Result<()> {
+		let mut pending: Vec<String> = names.to_vec();
+
+		while !pending.is_empty() {
+			self.queried.extend(pending.iter().cloned());
+
+			let results = zerr!(
+				self.aur.info(&pending),
+				"AUR",
+				"Cannot request info for packages"
+			)
+			.results;
+
+			pending.clear();
+
+			for package in results {
+				let name = match &package.Name {
+					Some(v) => v.clone(),
+					None => continue,
+				};
+
+				for dep in package.build_dependencies() {
+					if (self.is_repo)(&dep.name)
+						|| self.queried.contains(&dep.name)
+						|| pending.contains(&dep.name)
+					{
+						continue;
+					}
+
+					pending.push(dep.name);
+				}
+
+				self.packages.insert(name, package);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Find the AUR package that satisfies `dep`, either by name
+	/// or through its `Provides`.
+	fn provider(&self, dep: &DepSpec) -> Option<&str> {
+		if let Some((name, package)) =
+			self.packages.get_key_value(&dep.name)
+		{
+			if !package.satisfies(dep) {
+				warning!(
+					"resolver",
+					"Dependency {} cannot be satisfied by {} {}",
+					dep,
+					name,
+					package.Version.as_deref().unwrap_or("unknown")
+				);
+			}
+
+			return Some(name);
+		}
+
+		self.packages
+			.iter()
+			.find(|(_, package)| package.satisfies(dep))
+			.map(|(name, _)| name.as_str())
+	}
+
+	/// Depth-first topological sort. `stack` holds the packages
+	/// currently being visited so cycles can be reported.
+	fn visit(
+		&self,
+		name: &str,
+		stack: &mut Vec<String>,
+		order: &mut Vec<String>,
+	) -> Result<()> {
+		if order.iter().any(|x| x == name) {
+			return Ok(());
+		}
+
+		if let Some(i) = stack.iter().position(|x| x == name) {
+			let mut cycle = stack[i..].to_vec();
+			cycle.push(name.to_owned());
+
+			return Err(ZeusError::new(
+				"resolver".to_owned(),
+				format!(
+					"Dependency cycle detected: {}",
+					cycle.join(" -> ")
+				),
+			));
+		}
+
+		let package = match self.packages.get(name) {
+			Some(v) => v,
+			None => return Ok(()),
+		};
+
+		stack.push(name.to_owned());
+
+		for dep in package.build_dependencies() {
+			if (self.is_repo)(&dep.name) {
+				continue;
+			}
+
+			match self.provider(&dep) {
+				Some(provider) if provider != name => {
+					self.visit(provider, stack, order)?
+				},
+				Some(_) => {},
+				None => debug!(
+					"resolver",
+					"Assuming {} (needed by {}) is provided by the repositories",
+					dep,
+					name
+				),
+			}
+		}
+
+		stack.pop();
+		order.push(name.to_owned());
+
+		Ok(())
+	}
+}
+
+/// Resolve `names` and all of their AUR dependencies.
+///
+/// `is_repo` should return `true` for names that can be installed
+/// from the repositories. Those are left for `makepkg -s` to install.
+///
+/// The returned packages are sorted so that every package comes
+/// after the AUR packages it depends on.
+pub fn resolve<F>(
+	aur: &Aur,
+	names: &[String],
+	is_repo: F,
+) -> Result<Vec<Package>>
+where
+	F: Fn(&str) -> bool,
+{
+	let mut resolver = Resolver {
+		aur,
+		is_repo,
+		packages: HashMap::new(),
+		queried: HashSet::new(),
+	};
+
+	resolver.fetch(names)?;
+
+	let mut order: Vec<String> = vec![];
+	for name in names {
+		if !resolver.packages.contains_key(name) {
+			warning!("resolver", "Package {} was not found", name);
+			continue;
+		}
+
+		resolver.visit(name, &mut vec![], &mut order)?;
+	}
+
+	Ok(order
+		.iter()
+		.filter_map(|x| resolver.packages.remove(x))
+		.collect())
+}
@@ -0,0 +1,488 @@
+//! Static checks for risky code in PKGBUILDs and install scripts.
+//!
+//! The checks look at the text of the scripts, line by line, without
+//! running them. They cannot prove a package is safe, they only point
+//! the reviewer at the lines that deserve a closer look.
+
+use std::fmt;
+
+/// Something risky a script does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Risk {
+	/// A download is run by a shell, like `curl ... | sh`
+	PipeToShell,
+	/// A file is written outside `$pkgdir` and `$srcdir`
+	WriteOutsidePkgdir,
+	/// `sudo` is used
+	Sudo,
+	/// Code is hidden with base64
+	Base64,
+	/// A remote source has no checksum and is not pinned to a commit
+	UnpinnedSource,
+	/// The checksum of a file source is `SKIP`
+	SkippedChecksum,
+}
+
+impl Risk {
+	/// Check whether the risk should stop a build unless it is
+	/// forced. Sources without checksums are common enough in the
+	/// AUR that they are only worth a warning.
+	pub fn blocks(&self) -> bool {
+		!matches!(self, Risk::UnpinnedSource | Risk::SkippedChecksum)
+	}
+}
+
+impl fmt::Display for Risk {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let description = match self {
+			Risk::PipeToShell => "runs a download with a shell",
+			Risk::WriteOutsidePkgdir => "writes outside $pkgdir",
+			Risk::Sudo => "uses sudo",
+			Risk::Base64 => "decodes base64",
+			Risk::UnpinnedSource => "unpinned source",
+			Risk::SkippedChecksum => "skipped checksum",
+		};
+
+		write!(f, "{}", description)
+	}
+}
+
+/// A risk found in a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+	pub risk: Risk,
+	/// Line number, starting from 1
+	pub line: usize,
+	/// The offending code
+	pub text: String,
+}
+
+const SHELLS: &[&str] =
+	&["sh", "bash", "zsh", "dash", "python", "python3", "perl"];
+
+const DOWNLOADERS: &[&str] = &["curl", "wget"];
+
+/// Commands whose last argument is where they write
+const WRITERS: &[&str] =
+	&["install", "cp", "mv", "ln", "mkdir", "touch", "rm", "tee"];
+
+/// Places a build may write to
+const ALLOWED_PREFIXES: &[&str] = &[
+	"$pkgdir",
+	"${pkgdir",
+	"\"$pkgdir",
+	"\"${pkgdir",
+	"$srcdir",
+	"${srcdir",
+	"\"$srcdir",
+	"\"${srcdir",
+	"/dev/null",
+	"/dev/stdout",
+	"/dev/stderr",
+	"/dev/fd/",
+	"&",
+];
+
+const CHECKSUMS: &[&str] = &[
+	"cksums",
+	"md5sums",
+	"sha1sums",
+	"sha224sums",
+	"sha256sums",
+	"sha384sums",
+	"sha512sums",
+	"b2sums",
+];
+
+const VCS: &[&str] = &["git", "hg", "svn", "bzr", "fossil"];
+
+/// Arrays whose items are long strings of hex or base64 by design
+const KEY_ARRAYS: &[&str] = &["validpgpkeys"];
+
+/// Fragments that pin a VCS source to a fixed revision
+const VCS_PINS: &[&str] = &["#commit=", "#tag=", "#revision="];
+
+/// Scan `script`, a PKGBUILD or an install script, for risks.
+///
+/// Findings are sorted by line.
+///
+/// ```
+/// use zeus::scan::{scan, Risk};
+///
+/// let findings = scan("build() {\n  curl -s https://x.sh | sh\n}\n");
+///
+/// assert_eq!(findings[0].risk, Risk::PipeToShell);
+/// assert_eq!(findings[0].line, 2);
+/// ```
+pub fn scan(script: &str) -> Vec<Finding> {
+	let arrays = arrays(script);
+	let mut findings = vec![];
+
+	// checksums and keys are not hidden code
+	let hashes: Vec<&Array> = arrays
+		.iter()
+		.filter(|x| {
+			is_checksums(&x.name) || KEY_ARRAYS.contains(&&*x.name)
+		})
+		.collect();
+
+	for (i, line) in script.lines().enumerate() {
+		let code = strip_comment(line);
+		let words = words(code);
+
+		let mut risks = vec![];
+
+		if runs_download(code, &words) {
+			risks.push(Risk::PipeToShell);
+		}
+
+		if writes_outside(code, &words) {
+			risks.push(Risk::WriteOutsidePkgdir);
+		}
+
+		if words.contains(&"sudo") {
+			risks.push(Risk::Sudo);
+		}
+
+		let hashed = hashes
+			.iter()
+			.any(|x| (x.line..=x.end).contains(&(i + 1)));
+
+		if decodes_base64(&words, hashed) {
+			risks.push(Risk::Base64);
+		}
+
+		findings.extend(risks.into_iter().map(|risk| Finding {
+			risk,
+			line: i + 1,
+			text: line.trim().to_owned(),
+		}));
+	}
+
+	findings.extend(scan_sources(&arrays));
+	findings.sort_by_key(|x| x.line);
+
+	findings
+}
+
+/// `line` without its comment
+fn strip_comment(line: &str) -> &str {
+	let mut quote = None;
+	let mut last = ' ';
+
+	for (i, c) in line.char_indices() {
+		match (quote, c) {
+			(None, '\'' | '"') => quote = Some(c),
+			(Some(q), c) if c == q => quote = None,
+			(None, '#') if last.is_whitespace() => return &line[..i],
+			_ => {},
+		}
+
+		last = c;
+	}
+
+	line
+}
+
+/// The words of `code`, with pipes, redirections and command
+/// separators as words of their own
+fn words(code: &str) -> Vec<&str> {
+	let mut words = vec![];
+	let mut start = None;
+
+	for (i, c) in code.char_indices() {
+		let separator = matches!(c, '|' | ';' | '(' | ')' | '`');
+
+		if c.is_whitespace() || separator {
+			if let Some(s) = start.take() {
+				words.push(&code[s..i]);
+			}
+
+			if separator {
+				words.push(&code[i..i + 1]);
+			}
+		} else if start.is_none() {
+			start = Some(i);
+		}
+	}
+
+	if let Some(s) = start {
+		words.push(&code[s..]);
+	}
+
+	words
+}
+
+/// The command name of `word`, like `sh` for `/usr/bin/sh`
+fn command_name(word: &str) -> &str {
+	word.trim_matches(|x| x == '"' || x == '\'')
+		.rsplit('/')
+		.next()
+		.unwrap_or(word)
+}
+
+fn runs_download(code: &str, words: &[&str]) -> bool {
+	let is = |list: &[&str], word: &str| {
+		list.contains(&command_name(word))
+	};
+
+	let download = match words.iter().position(|x| is(DOWNLOADERS, x))
+	{
+		Some(v) => v,
+		None => return false,
+	};
+
+	// curl ... | sh
+	let piped = words[download..]
+		.windows(2)
+		.any(|x| x[0] == "|" && is(SHELLS, x[1]));
+
+	// sh -c "$(curl ...)" or bash <(curl ...)
+	let substituted = words[..download].iter().any(|x| is(SHELLS, x))
+		&& (code.contains("$(") || code.contains("<("));
+
+	piped || substituted
+}
+
+fn writes_outside(code: &str, words: &[&str]) -> bool {
+	let outside = |path: &str| {
+		let path = path.trim_start_matches('"');
+
+		(path.starts_with('/')
+			|| path.starts_with('~')
+			|| path.starts_with("$HOME"))
+			&& !ALLOWED_PREFIXES.iter().any(|x| path.starts_with(x))
+	};
+
+	// > /etc/foo
+	let redirected = code.match_indices('>').any(|(i, _)| {
+		let target =
+			code[i + 1..].trim_start_matches('>').trim_start();
+		target
+			.split_whitespace()
+			.next()
+			.map(outside)
+			.unwrap_or(false)
+	});
+
+	// cp foo /usr/bin/
+	let copied = words
+		.split(|x| matches!(*x, "|" | ";" | "&&" | "||"))
+		.any(|command| {
+			let mut args = command
+				.iter()
+				.skip_while(|x| **x == "sudo" || x.contains('='));
+
+			match args.next() {
+				Some(name)
+					if WRITERS.contains(&command_name(name)) =>
+				{
+					args.filter(|x| !x.starts_with('-'))
+						.last()
+						.map(|x| outside(x))
+						.unwrap_or(false)
+				},
+				_ => false,
+			}
+		});
+
+	redirected || copied
+}
+
+/// Check whether `words` decode base64 or hold a blob of it. Blobs
+/// are not looked for in lines of `hashed` arrays.
+fn decodes_base64(words: &[&str], hashed: bool) -> bool {
+	let decoding = words.windows(2).any(|x| {
+		command_name(x[0]) == "base64"
+			&& matches!(x[1], "-d" | "-D" | "--decode")
+	});
+
+	// openssl base64 -d or openssl enc -d -a
+	let openssl = words.iter().any(|x| command_name(x) == "openssl")
+		&& words.contains(&"-d")
+		&& words
+			.iter()
+			.any(|x| matches!(*x, "base64" | "-base64" | "-a"));
+
+	// long blobs of base64 are as suspicious as decoding them, but
+	// hex digits alone are far more likely a checksum
+	let blob = !hashed
+		&& words.iter().any(|x| {
+			x.split(|x: char| {
+				!(x.is_ascii_alphanumeric()
+					|| matches!(x, '+' | '/' | '='))
+			})
+			.any(|x| {
+				x.len() >= 80
+					&& !x.chars().all(|x| x.is_ascii_hexdigit())
+			})
+		});
+
+	decoding || openssl || blob
+}
+
+/// A bash array, like `source=(...)`
+struct Array {
+	name: String,
+	/// Line the array starts on
+	line: usize,
+	/// Line the array ends on
+	end: usize,
+	items: Vec<String>,
+}
+
+/// Find the arrays of `script`.
+fn arrays(script: &str) -> Vec<Array> {
+	let mut arrays = vec![];
+	let mut lines = script.lines().enumerate();
+
+	while let Some((i, line)) = lines.next() {
+		let mut end = i;
+
+		let (name, rest) =
+			match strip_comment(line).trim().split_once("=(") {
+				Some(v) => v,
+				None => continue,
+			};
+
+		if name.is_empty()
+			|| !name.chars().all(|x| x.is_alphanumeric() || x == '_')
+		{
+			continue;
+		}
+
+		let mut items = vec![];
+		let mut item = String::new();
+		let mut quote = None;
+		let mut rest = rest.to_owned();
+
+		'array: loop {
+			for c in rest.chars() {
+				match (quote, c) {
+					(None, '\'' | '"') => quote = Some(c),
+					(Some(q), c) if c == q => quote = None,
+					(None, ')') => break 'array,
+					(None, c) if c.is_whitespace() => {
+						if !item.is_empty() {
+							items.push(item);
+							item = String::new();
+						}
+					},
+					(_, c) => item.push(c),
+				}
+			}
+
+			// the array goes on in the next line
+			match quote {
+				Some(_) => item.push('\n'),
+				None if !item.is_empty() => {
+					items.push(item);
+					item = String::new();
+				},
+				None => {},
+			}
+
+			match lines.next() {
+				Some((j, line)) => {
+					end = j;
+					rest = strip_comment(line).to_owned()
+				},
+				None => break,
+			}
+		}
+
+		if !item.is_empty() {
+			items.push(item);
+		}
+
+		arrays.push(Array {
+			name: name.to_owned(),
+			line: i + 1,
+			end: end + 1,
+			items,
+		});
+	}
+
+	arrays
+}
+
+/// Check whether `name` is a checksum array, like `sha256sums` or
+/// `b2sums_x86_64`.
+fn is_checksums(name: &str) -> bool {
+	CHECKSUMS.iter().any(|c| {
+		name.strip_prefix(c)
+			.map(|x| x.is_empty() || x.starts_with('_'))
+			.unwrap_or(false)
+	})
+}
+
+/// Check the sources among `arrays` against their checksums.
+fn scan_sources(arrays: &[Array]) -> Vec<Finding> {
+	let mut findings = vec![];
+
+	for source in arrays.iter().filter(|x| {
+		x.name == "source" || x.name.starts_with("source_")
+	}) {
+		// `source_x86_64` goes with `sha256sums_x86_64`
+		let suffix = &source.name["source".len()..];
+
+		let checksums: Vec<&Array> = arrays
+			.iter()
+			.filter(|x| {
+				CHECKSUMS
+					.iter()
+					.any(|c| x.name.strip_prefix(c) == Some(suffix))
+			})
+			.collect();
+
+		for (i, item) in source.items.iter().enumerate() {
+			// `name::url` saves the download as `name`
+			let url =
+				item.split_once("::").map(|x| x.1).unwrap_or(item);
+
+			// local files come with the package and are reviewed
+			// with it
+			let scheme = match url.split_once("://") {
+				Some((scheme, _)) => scheme,
+				None => continue,
+			};
+
+			let sums: Vec<&str> = checksums
+				.iter()
+				.filter_map(|x| x.items.get(i))
+				.map(|x| x.as_str())
+				.collect();
+
+			if sums.iter().any(|x| *x != "SKIP") {
+				continue;
+			}
+
+			// `git+https` or `git`
+			let vcs = scheme
+				.split('+')
+				.next()
+				.map(|x| VCS.contains(&x))
+				.unwrap_or(false);
+
+			let risk = if vcs {
+				if VCS_PINS.iter().any(|x| url.contains(x)) {
+					continue;
+				}
+
+				Risk::UnpinnedSource
+			} else if sums.is_empty() {
+				Risk::UnpinnedSource
+			} else {
+				Risk::SkippedChecksum
+			};
+
+			findings.push(Finding {
+				risk,
+				line: source.line,
+				text: item.clone(),
+			});
+		}
+	}
+
+	findings
+}
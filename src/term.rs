@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Display;
 use std::io;
 use std::io::prelude::*;
@@ -65,13 +65,15 @@ impl Terminal {
 		)?;
 		self.output.flush()?;
 
-		let mut answer: [u8; 1] = [0; 1];
-		self.input.read(&mut answer)?;
+		// read the whole line, so the rest of it is not taken as the
+		// answer to the next question
+		let mut answer = String::with_capacity(4);
+		self.input.read_line(&mut answer)?;
 
-		match answer[0] as char {
-			'y' | 'Y' => Ok(true),
-			'n' | 'N' => Ok(false),
-			'\n' => Ok(default),
+		match answer.trim().chars().next() {
+			Some('y' | 'Y') => Ok(true),
+			Some('n' | 'N') => Ok(false),
+			None => Ok(default),
 			_ => Ok(false),
 		}
 	}
@@ -170,6 +172,61 @@ impl Terminal {
 	}
 }
 
+/// Parse a selection of numbered items, like `1 2 3`, `1-3` or `^4`,
+/// into the indexes of the selected items out of `count`.
+///
+/// Numbers start from 1. Items after a `^` are excluded, and if there
+/// are only exclusions, everything else is selected.
+pub fn parse_selection(input: &str, count: usize) -> Vec<usize> {
+	let mut included = BTreeSet::new();
+	let mut excluded = BTreeSet::new();
+	let (mut includes, mut excludes) = (false, false);
+
+	for word in input
+		.split(|x: char| x.is_whitespace() || x == ',')
+		.filter(|x| !x.is_empty())
+	{
+		let (word, exclusion) = match word.strip_prefix('^') {
+			Some(v) => (v, true),
+			None => (word, false),
+		};
+
+		let (start, end) =
+			word.split_once('-').unwrap_or((word, word));
+
+		let (start, end): (usize, usize) =
+			match (start.parse(), end.parse()) {
+				(Ok(start), Ok(end)) => (start, end),
+				_ => continue,
+			};
+
+		let set = match exclusion {
+			true => {
+				excludes = true;
+				&mut excluded
+			},
+			false => {
+				includes = true;
+				&mut included
+			},
+		};
+
+		for number in start.min(end)..=end.max(start).min(count) {
+			if number != 0 {
+				set.insert(number - 1);
+			}
+		}
+	}
+
+	// only exclusions select everything else, even if they are all
+	// out of range
+	if excludes && !includes {
+		included = (0..count).collect();
+	}
+
+	included.difference(&excluded).copied().collect()
+}
+
 impl Default for Terminal {
 	fn default() -> Self {
 		Self {
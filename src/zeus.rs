@@ -1,7 +1,13 @@
 mod cli;
+mod db;
+mod format;
 mod lock;
 mod message;
 mod ops;
+mod pacman;
+mod refine;
+mod resolve;
+mod scan;
 mod term;
 mod unix;
 
@@ -12,7 +18,9 @@ pub mod log;
 
 pub mod machine;
 
+use std::path::Path;
 use std::process::exit;
+use std::time::Duration;
 
 fn main() {
 	let args = cli::build().get_matches();
@@ -33,10 +41,29 @@ fn main() {
 		_ => {},
 	}
 
-	let (command_name, command_args) = args.subcommand().unwrap();
+	let (command_name, command_args) = cli::subcommand(&args);
+
+	let build_dir = Path::new(args.value_of("builddir").unwrap());
+
+	// validated in cli.rs
+	let cache_ttl =
+		Duration::from_secs(args.value_of_t("cache-ttl").unwrap());
+
+	let mut aur = aur::Aur::new()
+		.host(args.value_of("aur").unwrap().to_owned())
+		.refresh(args.is_present("refresh"));
+
+	if let Some(dir) = args.value_of("aurdir") {
+		aur = aur.transport(aur::FileTransport::new(dir));
+	} else if args.is_present("archive") {
+		aur = aur
+			.archive(build_dir.join(".zeus.meta.json.gz"), cache_ttl);
+	} else {
+		aur = aur.cache(build_dir.join(".zeus.cache"), cache_ttl);
+	}
 
 	let mut cfg = config::AppConfig {
-		operation: config::Operation::from(command_name),
+		operation: config::Operation::from(command_name.as_str()),
 
 		debug: args.is_present("debug"),
 		force: args.is_present("force"),
@@ -44,9 +71,7 @@ fn main() {
 		// this should never fail, we set the default value in cli.rs
 		build_dir: args.value_of("builddir").unwrap().to_owned(),
 
-		aur: aur::Aur::new()
-			.host(args.value_of("aur").unwrap().to_owned())
-			.build(),
+		aur: aur.build(),
 
 		runtime: args.value_of("rt").unwrap().to_owned(),
 		runtime_dir: args.value_of("rtdir").unwrap().to_owned(),
@@ -59,7 +84,7 @@ fn main() {
 		cfg.build_args.push("-f".to_owned());
 	}
 
-	let res = ops::run_operation(&mut term, cfg, command_args);
+	let res = ops::run_operation(&mut term, cfg, &command_args);
 
 	match res {
 		Ok(_) => exit(0),
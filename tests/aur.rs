@@ -0,0 +1,497 @@
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use zeus::aur::{
+	group_by_base, mock::MockServer, Aur, AurError, By,
+	FileTransport, Package,
+};
+use zeus::error::ZeusError;
+use zeus::resolve::resolve;
+
+const REPO_PACKAGES: &[&str] = &[
+	"glibc",
+	"git",
+	"cmake",
+	"cargo",
+	"docker",
+	"python",
+	"python-pytest",
+];
+
+fn fixtures() -> Vec<Package> {
+	serde_json::from_str(include_str!("fixtures/packages.json"))
+		.unwrap()
+}
+
+fn server() -> MockServer {
+	MockServer::new(fixtures()).unwrap()
+}
+
+/// Create a fresh, empty directory.
+fn temp_dir(name: &str) -> PathBuf {
+	let dir = std::env::temp_dir().join(format!(
+		"zeus-test-{}-{}",
+		std::process::id(),
+		name
+	));
+
+	let _ = fs::remove_dir_all(&dir);
+	fs::create_dir_all(&dir).unwrap();
+
+	dir
+}
+
+/// Write the fixtures to a fresh directory, one file per package.
+fn fixtures_dir(name: &str) -> PathBuf {
+	let dir = temp_dir(name);
+
+	for package in fixtures() {
+		fs::write(
+			dir.join(format!(
+				"{}.json",
+				package.Name.as_ref().unwrap()
+			)),
+			serde_json::to_vec(&package).unwrap(),
+		)
+		.unwrap();
+	}
+
+	dir
+}
+
+fn names(packages: &[Package]) -> Vec<&str> {
+	packages.iter().filter_map(|x| x.Name.as_deref()).collect()
+}
+
+fn is_repo(name: &str) -> bool {
+	REPO_PACKAGES.contains(&name)
+}
+
+#[test]
+fn info_returns_requested_packages() {
+	let server = server();
+
+	let res = server.aur().info(&["zeus", "foo-git"]).unwrap();
+
+	assert_eq!(res.r#type, "multiinfo");
+	assert_eq!(res.resultcount, 2);
+	assert_eq!(names(&res.results), ["zeus", "foo-git"]);
+	assert_eq!(
+		res.results[1].Depends.as_deref(),
+		Some(&["glibc".to_owned(), "libfoo>=1.0".to_owned()][..])
+	);
+}
+
+#[test]
+fn info_skips_unknown_packages() {
+	let server = server();
+
+	let res = server.aur().info(&["zeus", "does-not-exist"]).unwrap();
+
+	assert_eq!(names(&res.results), ["zeus"]);
+}
+
+#[test]
+fn search_by_name_and_description() {
+	let server = server();
+	let aur = server.aur();
+
+	let res = aur.search(By::Name, &["zeus"]).unwrap();
+	assert_eq!(res.r#type, "search");
+	assert_eq!(names(&res.results), ["zeus", "zeus-bin"]);
+
+	let res = aur.search(By::NameDesc, &["library"]).unwrap();
+	assert_eq!(names(&res.results), ["libfoo-git"]);
+
+	let res = aur.search(By::Name, &["library"]).unwrap();
+	assert!(res.results.is_empty());
+}
+
+#[test]
+fn search_by_maintainer_and_depends() {
+	let server = server();
+	let aur = server.aur();
+
+	let res = aur.search(By::Maintainer, &["alice"]).unwrap();
+	assert_eq!(names(&res.results), ["foo-git", "libfoo-git"]);
+
+	let res = aur.search(By::Depends, &["libfoo"]).unwrap();
+	assert_eq!(names(&res.results), ["foo-git"]);
+
+	let res = aur.search(By::MakeDepends, &["bar-git"]).unwrap();
+	assert_eq!(names(&res.results), ["foo-git"]);
+
+	// search results do not carry dependencies
+	assert!(res.results[0].Depends.is_none());
+}
+
+#[test]
+fn requests_are_recorded() {
+	let server = server();
+
+	server.aur().info(&["zeus"]).unwrap();
+
+	let requests = server.requests();
+	assert_eq!(requests.len(), 1);
+	assert!(requests[0].starts_with("/rpc/?v=5&type=info"));
+}
+
+#[test]
+fn long_info_requests_are_split() {
+	let server = server();
+	let aur = Aur::new()
+		.protocol("http".to_owned())
+		.host(server.addr().to_string())
+		.max_url_len(80)
+		.jobs(2)
+		.build();
+
+	let wanted =
+		["zeus", "zeus-bin", "foo-git", "libfoo-git", "bar-git"];
+	let res = aur.info(&wanted).unwrap();
+
+	assert_eq!(res.resultcount, wanted.len());
+	assert_eq!(names(&res.results), wanted);
+	assert!(server.requests().len() > 1);
+}
+
+#[test]
+fn search_matches_all_keywords() {
+	let server = server();
+	let aur = server.aur();
+
+	let res = aur.search(By::NameDesc, &["zeus", "bin"]).unwrap();
+	assert_eq!(res.resultcount, 1);
+	assert_eq!(names(&res.results), ["zeus-bin"]);
+	assert_eq!(server.requests().len(), 2);
+
+	let res = aur
+		.search(By::NameDesc, &["zeus", "library", "zeus"])
+		.unwrap();
+	assert!(res.results.is_empty());
+	assert_eq!(server.requests().len(), 4);
+}
+
+#[test]
+fn suggest_returns_names() {
+	let server = server();
+	let aur = server.aur();
+
+	assert_eq!(aur.suggest("zeus").unwrap(), ["zeus", "zeus-bin"]);
+	assert_eq!(aur.suggest("lib").unwrap(), ["libc++", "libfoo-git"]);
+	assert!(aur.suggest("nothing").unwrap().is_empty());
+	assert_eq!(
+		aur.suggest_pkgbase("cycle").unwrap(),
+		["cycle-a", "cycle-b"]
+	);
+
+	let dir = fixtures_dir("suggest");
+	let aur = Aur::new().transport(FileTransport::new(&dir)).build();
+
+	assert_eq!(aur.suggest("zeus").unwrap(), ["zeus", "zeus-bin"]);
+
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn rpc_errors_are_reported() {
+	let server = server();
+	let aur = server.aur();
+
+	match aur.search(By::Name, &["z"]) {
+		Err(AurError::Rpc(message)) => {
+			assert_eq!(message, "Query arg too small.")
+		},
+		v => panic!("expected an RPC error, got {:?}", v),
+	}
+
+	server.max_results(1);
+	let err = aur.search(By::Name, &["zeus"]).unwrap_err();
+	assert!(matches!(err, AurError::TooManyResults));
+
+	let err = ZeusError::from(err);
+	assert_eq!(err.caller, "AUR");
+	assert!(err.message.contains("narrow your search"));
+}
+
+#[test]
+fn rate_limits_are_reported() {
+	let server = server();
+	let aur = server.aur();
+
+	server.rate_limit(30);
+	let err = aur.info(&["zeus"]).unwrap_err();
+	assert!(matches!(err, AurError::RateLimited(Some(30))));
+	assert!(ZeusError::from(err)
+		.message
+		.contains("retry after 30 s"));
+
+	server.rate_limit(0);
+	assert!(aur.info(&["zeus"]).is_ok());
+}
+
+#[test]
+fn responses_are_cached() {
+	let dir = temp_dir("cache");
+	let server = server();
+	let aur = |ttl: u64, refresh: bool| {
+		Aur::new()
+			.protocol("http".to_owned())
+			.host(server.addr().to_string())
+			.cache(&dir, Duration::from_secs(ttl))
+			.refresh(refresh)
+			.build()
+	};
+
+	let res = aur(600, false).info(&["zeus"]).unwrap();
+	assert_eq!(names(&res.results), ["zeus"]);
+
+	let res = aur(600, false).info(&["zeus"]).unwrap();
+	assert_eq!(names(&res.results), ["zeus"]);
+	assert_eq!(server.requests().len(), 1);
+
+	aur(600, true).info(&["zeus"]).unwrap();
+	aur(0, false).info(&["zeus"]).unwrap();
+	assert_eq!(server.requests().len(), 3);
+
+	// errors are not cached
+	aur(600, false).search(By::Name, &["z"]).unwrap_err();
+	aur(600, false).search(By::Name, &["z"]).unwrap_err();
+	assert_eq!(server.requests().len(), 5);
+
+	// stale responses are used when the AUR is unreachable
+	let offline = aur(0, false);
+	drop(server);
+
+	let res = offline.info(&["zeus"]).unwrap();
+	assert_eq!(names(&res.results), ["zeus"]);
+	assert!(offline.info(&["foo-git"]).is_err());
+
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn stale_responses_survive_new_ones() {
+	let dir = temp_dir("cache-stale");
+	let server = server();
+	let aur = Aur::new()
+		.protocol("http".to_owned())
+		.host(server.addr().to_string())
+		.cache(&dir, Duration::ZERO)
+		.build();
+
+	aur.info(&["zeus"]).unwrap();
+
+	// every response is stale right away, storing another one must
+	// not delete the first
+	thread::sleep(Duration::from_millis(10));
+	aur.info(&["foo-git"]).unwrap();
+	assert_eq!(server.requests().len(), 2);
+
+	drop(server);
+
+	let res = aur.info(&["zeus"]).unwrap();
+	assert_eq!(names(&res.results), ["zeus"]);
+	let res = aur.info(&["foo-git"]).unwrap();
+	assert_eq!(names(&res.results), ["foo-git"]);
+
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn expired_responses_are_pruned() {
+	let dir = temp_dir("cache-prune");
+	let server = server();
+	let aur = Aur::new()
+		.protocol("http".to_owned())
+		.host(server.addr().to_string())
+		.cache(&dir, Duration::from_secs(600))
+		.build();
+
+	fs::create_dir_all(&dir).unwrap();
+
+	let expired = dir.join("0000000000000000.json");
+	let fresh = dir.join("0000000000000001.json");
+	let other = dir.join("notes.txt");
+
+	for path in [&expired, &fresh, &other] {
+		fs::write(path, "{}").unwrap();
+	}
+
+	let age = |days: u64| {
+		SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60)
+	};
+
+	// stale, but still kept to fall back on
+	File::options()
+		.write(true)
+		.open(&fresh)
+		.unwrap()
+		.set_modified(age(1))
+		.unwrap();
+
+	for path in [&expired, &other] {
+		File::options()
+			.write(true)
+			.open(path)
+			.unwrap()
+			.set_modified(age(365))
+			.unwrap();
+	}
+
+	aur.info(&["zeus"]).unwrap();
+
+	assert!(!expired.exists());
+	assert!(fresh.exists());
+	assert!(other.exists());
+
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn archive_answers_queries() {
+	let dir = temp_dir("archive");
+	let path = dir.join("meta.json.gz");
+	let server = server();
+	let aur = || {
+		Aur::new()
+			.protocol("http".to_owned())
+			.host(server.addr().to_string())
+			.archive(&path, Duration::from_secs(600))
+			.build()
+	};
+
+	let res = aur().info(&["zeus", "foo-git", "nope"]).unwrap();
+	assert_eq!(names(&res.results), ["zeus", "foo-git"]);
+
+	let res = aur().search(By::Maintainer, &["alice"]).unwrap();
+	assert_eq!(names(&res.results), ["foo-git", "libfoo-git"]);
+
+	let packages =
+		resolve(&aur(), &["foo-git".to_owned()], is_repo).unwrap();
+	assert_eq!(names(&packages), ["bar-git", "foo-git"]);
+
+	// the archive is downloaded once and reused while it is fresh
+	let requests = server.requests();
+	assert_eq!(requests, ["/packages-meta-ext-v1.json.gz"]);
+
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn resolve_orders_dependencies_first() {
+	let server = server();
+
+	let packages =
+		resolve(&server.aur(), &["foo-git".to_owned()], is_repo)
+			.unwrap();
+
+	assert_eq!(names(&packages), ["bar-git", "foo-git"]);
+}
+
+#[test]
+fn resolve_uses_provides() {
+	let server = server();
+
+	// foo-git needs libfoo>=1.0, which only libfoo-git provides
+	let packages = resolve(
+		&server.aur(),
+		&["foo-git".to_owned(), "libfoo-git".to_owned()],
+		is_repo,
+	)
+	.unwrap();
+
+	assert_eq!(
+		names(&packages),
+		["libfoo-git", "bar-git", "foo-git"]
+	);
+}
+
+#[test]
+fn resolve_keeps_requested_packages() {
+	let server = server();
+
+	let packages = resolve(
+		&server.aur(),
+		&["zeus".to_owned(), "libfoo-git".to_owned()],
+		is_repo,
+	)
+	.unwrap();
+
+	assert_eq!(names(&packages), ["zeus", "libfoo-git"]);
+}
+
+#[test]
+fn resolve_drops_unknown_packages() {
+	let server = server();
+
+	let packages = resolve(
+		&server.aur(),
+		&["does-not-exist".to_owned(), "zeus".to_owned()],
+		is_repo,
+	)
+	.unwrap();
+
+	assert_eq!(names(&packages), ["zeus"]);
+}
+
+#[test]
+fn resolve_detects_cycles() {
+	let server = server();
+
+	let err =
+		resolve(&server.aur(), &["cycle-a".to_owned()], is_repo)
+			.unwrap_err();
+
+	assert!(err.message.contains("cycle-a -> cycle-b -> cycle-a"));
+}
+
+#[test]
+fn split_packages_share_their_base() {
+	let server = server();
+
+	let packages = resolve(
+		&server.aur(),
+		&["qux-docs".to_owned(), "qux".to_owned()],
+		is_repo,
+	)
+	.unwrap();
+
+	assert_eq!(names(&packages), ["qux-docs", "qux"]);
+	assert!(packages.iter().all(|x| x.base() == Some("qux")));
+
+	// and are built together
+	let bases = group_by_base(&packages);
+	assert_eq!(bases.len(), 1);
+	assert_eq!(bases[0].0, "qux");
+	assert_eq!(bases[0].1.len(), 2);
+
+	// packages that are not split are their own base
+	let package = Package {
+		Name: Some("zeus".to_owned()),
+		..Default::default()
+	};
+	assert_eq!(package.base(), Some("zeus"));
+}
+
+#[test]
+fn file_transport_answers_queries() {
+	let dir = fixtures_dir("file-transport");
+	let aur = Aur::new().transport(FileTransport::new(&dir)).build();
+
+	let res = aur.info(&["foo-git", "does-not-exist"]).unwrap();
+	assert_eq!(res.r#type, "multiinfo");
+	assert_eq!(names(&res.results), ["foo-git"]);
+
+	let res = aur.search(By::Name, &["zeus"]).unwrap();
+	assert_eq!(res.r#type, "search");
+	assert_eq!(names(&res.results), ["zeus", "zeus-bin"]);
+
+	let packages =
+		resolve(&aur, &["foo-git".to_owned()], is_repo).unwrap();
+	assert_eq!(names(&packages), ["bar-git", "foo-git"]);
+
+	fs::remove_dir_all(&dir).unwrap();
+}
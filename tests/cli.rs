@@ -0,0 +1,81 @@
+use clap::ArgMatches;
+
+use zeus::cli;
+
+fn parse(args: &[&str]) -> (String, ArgMatches) {
+	let args = cli::build()
+		.try_get_matches_from(["zeus"].iter().chain(args))
+		.unwrap();
+
+	cli::subcommand(&args)
+}
+
+fn keywords(args: &ArgMatches) -> Vec<&str> {
+	args.values_of("keywords").unwrap_or_default().collect()
+}
+
+#[test]
+fn global_options_before_subcommands() {
+	let (name, args) = parse(&["-d", "sync", "-u"]);
+	assert_eq!(name, "sync");
+	assert!(args.is_present("upgrade"));
+
+	let (name, args) = parse(&["--force", "query", "-i", "x"]);
+	assert_eq!(name, "query");
+	assert!(args.is_present("info"));
+	assert!(!args.is_present("select"));
+	assert_eq!(keywords(&args), ["x"]);
+
+	let (name, args) = parse(&[
+		"--builddir",
+		"/tmp/zb",
+		"--aurdir",
+		"/tmp/za",
+		"query",
+		"zeus",
+	]);
+	assert_eq!(name, "query");
+	assert!(!args.is_present("select"));
+	assert_eq!(keywords(&args), ["zeus"]);
+}
+
+#[test]
+fn short_flags() {
+	let (name, args) = parse(&["-Su", "foo"]);
+	assert_eq!(name, "sync");
+	assert!(args.is_present("upgrade"));
+
+	let (name, _) = parse(&["--color", "never", "-Qi", "foo"]);
+	assert_eq!(name, "query");
+}
+
+#[test]
+fn keywords_select_packages() {
+	let (name, args) = parse(&["foo", "bar"]);
+	assert_eq!(name, "query");
+	assert!(args.is_present("select"));
+	assert_eq!(keywords(&args), ["foo", "bar"]);
+
+	let (name, args) = parse(&["-d", "foo"]);
+	assert_eq!(name, "query");
+	assert!(args.is_present("select"));
+	assert_eq!(keywords(&args), ["foo"]);
+}
+
+#[test]
+fn subcommand_is_required() {
+	assert!(cli::build()
+		.try_get_matches_from(["zeus", "-d"])
+		.is_err());
+}
+
+#[test]
+fn select_uses_the_builder_name() {
+	let (_, sync) = parse(&["sync"]);
+	let (_, args) = parse(&["foo"]);
+	assert_eq!(args.value_of("name"), sync.value_of("name"));
+
+	let (_, args) =
+		parse(&["query", "--select", "--name", "b", "foo"]);
+	assert_eq!(args.value_of("name"), Some("b"));
+}
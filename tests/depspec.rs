@@ -0,0 +1,180 @@
+use zeus::aur::{DepOp, DepSpec, Package, Version};
+
+#[test]
+fn parse() {
+	let cases: &[(&str, &str, Option<DepOp>, Option<&str>)] = &[
+		("foo", "foo", None, None),
+		("foo<1.0", "foo", Some(DepOp::Lt), Some("1.0")),
+		("foo<=1.0", "foo", Some(DepOp::Le), Some("1.0")),
+		("foo=1.0", "foo", Some(DepOp::Eq), Some("1.0")),
+		("foo>=1.0", "foo", Some(DepOp::Ge), Some("1.0")),
+		("foo>1.0", "foo", Some(DepOp::Gt), Some("1.0")),
+		("foo>=2:1.0-3", "foo", Some(DepOp::Ge), Some("2:1.0-3")),
+		(
+			"libfoo.so=1-64",
+			"libfoo.so",
+			Some(DepOp::Eq),
+			Some("1-64"),
+		),
+		("foo: for bar support", "foo", None, None),
+		("foo>=1.0: for bar", "foo", Some(DepOp::Ge), Some("1.0")),
+		(" foo ", "foo", None, None),
+	];
+
+	for (s, name, op, version) in cases {
+		let spec = DepSpec::from(*s);
+
+		assert_eq!(spec.name, *name, "name of {:?}", s);
+		assert_eq!(spec.op, *op, "op of {:?}", s);
+		assert_eq!(
+			spec.version.as_ref().map(|x| x.to_string()),
+			version.map(|x| x.to_owned()),
+			"version of {:?}",
+			s
+		);
+	}
+}
+
+#[test]
+fn display() {
+	for s in
+		["foo", "foo<1", "foo<=1", "foo=1:1-1", "foo>=1", "foo>1"]
+	{
+		assert_eq!(DepSpec::from(s).to_string(), s);
+	}
+}
+
+#[test]
+fn accepts() {
+	let cases: &[(&str, Option<&str>, bool)] = &[
+		// no constraint
+		("foo", Some("1.0-1"), true),
+		("foo", None, true),
+		// each operator
+		("foo<1.0", Some("0.9-1"), true),
+		("foo<1.0", Some("1.0-1"), false),
+		("foo<1.0", Some("1.1-1"), false),
+		("foo<=1.0", Some("0.9-1"), true),
+		("foo<=1.0", Some("1.0-1"), true),
+		("foo<=1.0", Some("1.1-1"), false),
+		("foo=1.0", Some("0.9-1"), false),
+		("foo=1.0", Some("1.0-1"), true),
+		("foo=1.0", Some("1.1-1"), false),
+		("foo>=1.0", Some("0.9-1"), false),
+		("foo>=1.0", Some("1.0-1"), true),
+		("foo>=1.0", Some("1.1-1"), true),
+		("foo>1.0", Some("0.9-1"), false),
+		("foo>1.0", Some("1.0-1"), false),
+		("foo>1.0", Some("1.1-1"), true),
+		// a constraint without pkgrel ignores the pkgrel
+		("foo=1.0", Some("1.0-7"), true),
+		("foo>1.0", Some("1.0-7"), false),
+		// a constraint with pkgrel checks it
+		("foo=1.0-1", Some("1.0-2"), false),
+		("foo>=1.0-2", Some("1.0-2"), true),
+		("foo>1.0-1", Some("1.0-2"), true),
+		("foo>=1.0-2", Some("1.0"), true),
+		// epochs
+		("foo>=1:1.0", Some("2.0-1"), false),
+		("foo>=1:1.0", Some("1:1.0-1"), true),
+		("foo<1:0.1", Some("9.9-1"), true),
+		("foo=0:1.0", Some("1.0-1"), true),
+		("foo>1.0", Some("1:0.1-1"), true),
+		// unknown versions never satisfy a constraint
+		("foo>=1.0", None, false),
+	];
+
+	for (spec, version, expected) in cases {
+		let version = version.map(Version::from);
+
+		assert_eq!(
+			DepSpec::from(*spec).accepts(version.as_ref()),
+			*expected,
+			"{:?} accepts {:?}",
+			spec,
+			version
+		);
+	}
+}
+
+#[test]
+fn provisions() {
+	let cases: &[(&str, &str, bool)] = &[
+		("libfoo.so", "libfoo.so=1-64", true),
+		("libfoo.so", "libfoo.so", true),
+		("libfoo.so=1-64", "libfoo.so=1-64", true),
+		("libfoo.so>=2", "libfoo.so=1-64", false),
+		("libfoo.so<2", "libfoo.so=1-64", true),
+		// an unversioned provision cannot satisfy a versioned spec
+		("libfoo.so>=1", "libfoo.so", false),
+		("libfoo.so=1", "libfoo.so", false),
+		("libbar.so", "libfoo.so=1-64", false),
+	];
+
+	for (spec, provision, expected) in cases {
+		assert_eq!(
+			DepSpec::from(*spec)
+				.satisfied_by_provision(&DepSpec::from(*provision)),
+			*expected,
+			"{:?} satisfied by {:?}",
+			spec,
+			provision
+		);
+	}
+}
+
+#[test]
+fn package_satisfies() {
+	let package = Package {
+		Name: Some("foo-git".to_owned()),
+		Version: Some("1:2.0.r10.g1234abc-1".to_owned()),
+		Provides: Some(vec![
+			"foo=2.0".to_owned(),
+			"libfoo.so=2-64".to_owned(),
+			"foo-bin".to_owned(),
+		]),
+		..Default::default()
+	};
+
+	let cases: &[(&str, bool)] = &[
+		// by name
+		("foo-git", true),
+		("foo-git>=1:2.0", true),
+		("foo-git>1:2.0", true),
+		("foo-git<1:2.0", false),
+		("foo-git>=2.0", true),
+		("foo-git=2.0", false),
+		// by a versioned provision
+		("foo", true),
+		("foo=2.0", true),
+		("foo>=1.0", true),
+		("foo<2.0", false),
+		("foo>2.0", false),
+		("libfoo.so>=2", true),
+		// by an unversioned provision
+		("foo-bin", true),
+		("foo-bin>=1.0", false),
+		// not at all
+		("bar", false),
+	];
+
+	for (spec, expected) in cases {
+		assert_eq!(
+			package.satisfies(&DepSpec::from(*spec)),
+			*expected,
+			"foo-git satisfies {:?}",
+			spec
+		);
+	}
+}
+
+#[test]
+fn package_without_version() {
+	let package = Package {
+		Name: Some("foo".to_owned()),
+		..Default::default()
+	};
+
+	assert!(package.satisfies(&DepSpec::from("foo")));
+	assert!(!package.satisfies(&DepSpec::from("foo>=1.0")));
+}
@@ -0,0 +1,89 @@
+use zeus::aur::{mock::MockServer, By, Package};
+
+/// Keywords that mean something in a URL
+const NASTY: &[&str] = &[
+	"a b",
+	"a&b",
+	"a&type=info",
+	"a#b",
+	"a=b",
+	"a%20b",
+	"a/b?c",
+	"arg[]=zeus",
+	"ünïcödé",
+	"'\"<>",
+];
+
+fn server() -> MockServer {
+	let packages: Vec<Package> =
+		serde_json::from_str(include_str!("fixtures/packages.json"))
+			.unwrap();
+
+	MockServer::new(packages).unwrap()
+}
+
+fn names(packages: &[Package]) -> Vec<&str> {
+	packages.iter().filter_map(|x| x.Name.as_deref()).collect()
+}
+
+#[test]
+fn info_encodes_names() {
+	let server = server();
+
+	let res = server.aur().info(&["libc++"]).unwrap();
+	assert_eq!(names(&res.results), ["libc++"]);
+
+	let target = &server.requests()[0];
+	assert!(target.contains("libc%2B%2B"), "{}", target);
+}
+
+#[test]
+fn search_encodes_keywords() {
+	let server = server();
+
+	let res = server.aur().search(By::Name, &["c++"]).unwrap();
+	assert_eq!(names(&res.results), ["libc++"]);
+
+	let res =
+		server.aur().search(By::NameDesc, &["c++ runtime"]).unwrap();
+	assert_eq!(names(&res.results), ["libc++"]);
+}
+
+#[test]
+fn nasty_keywords_are_sent_verbatim() {
+	let server = server();
+	let aur = server.aur();
+
+	for keyword in NASTY {
+		let res = aur.search(By::Name, &[keyword]).unwrap();
+
+		assert_eq!(
+			res.r#type, "search",
+			"searching for {:?}",
+			keyword
+		);
+		assert!(
+			res.results.is_empty(),
+			"searching for {:?}",
+			keyword
+		);
+	}
+
+	for target in server.requests() {
+		// every keyword ends up in exactly one argument
+		assert_eq!(target.matches("type=").count(), 1, "{}", target);
+		assert_eq!(target.matches("arg=").count(), 1, "{}", target);
+		assert!(!target.contains(['#', ' ', '"', '<']), "{}", target);
+	}
+}
+
+#[test]
+fn nasty_names_are_not_found() {
+	let server = server();
+
+	let res = server.aur().info(NASTY).unwrap();
+
+	assert_eq!(res.r#type, "multiinfo");
+	assert!(res.results.is_empty());
+	assert_eq!(server.requests()[0].matches("type=").count(), 1);
+}
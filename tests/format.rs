@@ -0,0 +1,66 @@
+use zeus::aur::Package;
+use zeus::format::Template;
+
+fn package() -> Package {
+	Package {
+		Name: Some("zeus".to_owned()),
+		Version: Some("1.0-1".to_owned()),
+		Depends: Some(vec!["docker".to_owned(), "git".to_owned()]),
+		NumVotes: Some(3),
+		Popularity: Some(0.1),
+		..Default::default()
+	}
+}
+
+#[test]
+fn render() {
+	let cases: &[(&str, &str)] = &[
+		("", ""),
+		("{Name}", "zeus"),
+		("{Name} {Version}", "zeus 1.0-1"),
+		("name: {Name}!", "name: zeus!"),
+		("{Name}{Version}", "zeus1.0-1"),
+		// escapes
+		(r"{Name}\t{Version}\n", "zeus\t1.0-1\n"),
+		(r"a\\b", r"a\b"),
+		(r"\x", r"\x"),
+		(r"a\", r"a\"),
+		// literal braces
+		("{{Name}}", "{Name}"),
+		("{{{Name}}}", "{zeus}"),
+		("}}{{", "}{"),
+		// lists, numbers and missing values
+		("{Depends}", "docker git"),
+		("{NumVotes} {Popularity}", "3 0.1"),
+		("[{Maintainer}]", "[]"),
+	];
+
+	for (template, expected) in cases {
+		assert_eq!(
+			Template::parse::<Package>(template)
+				.unwrap()
+				.render(&package()),
+			*expected,
+			"template {:?}",
+			template
+		);
+	}
+}
+
+#[test]
+fn invalid_templates() {
+	let cases: &[(&str, &str)] = &[
+		("{Nope}", "Unknown field: Nope"),
+		("{name}", "Unknown field: name"),
+		("{}", "Unknown field: "),
+		("{Name", "Unclosed field in template: {Name"),
+		("{Name} }", "Unmatched } in template"),
+	];
+
+	for (template, message) in cases {
+		let err = Template::parse::<Package>(template).unwrap_err();
+
+		assert_eq!(err.caller, "format");
+		assert_eq!(err.message, *message, "template {:?}", template);
+	}
+}
@@ -0,0 +1,136 @@
+use zeus::aur::Package;
+use zeus::refine::{Refine, SortBy};
+
+fn packages() -> Vec<Package> {
+	let package = |name: &str,
+	               votes: u64,
+	               popularity: f32,
+	               modified: u64,
+	               maintainer: Option<&str>,
+	               out_of_date: Option<u64>| Package {
+		Name: Some(name.to_owned()),
+		NumVotes: Some(votes),
+		Popularity: Some(popularity),
+		LastModified: Some(modified),
+		Maintainer: maintainer.map(|x| x.to_owned()),
+		OutOfDate: out_of_date,
+		..Default::default()
+	};
+
+	vec![
+		package("b", 10, 0.5, 300, Some("alice"), None),
+		package("d", 0, 0.0, 100, None, None),
+		package("a", 50, 0.1, 200, Some("bob"), Some(1)),
+		package("c", 10, 2.0, 400, Some("carol"), None),
+	]
+}
+
+fn names(refine: Refine) -> Vec<String> {
+	let mut packages = packages();
+	refine.apply(&mut packages);
+
+	packages.into_iter().filter_map(|x| x.Name).collect()
+}
+
+#[test]
+fn sort_keys() {
+	let cases: &[(&str, SortBy)] = &[
+		("votes", SortBy::Votes),
+		("popularity", SortBy::Popularity),
+		("modified", SortBy::Modified),
+		("name", SortBy::Name),
+	];
+
+	for (s, sort) in cases {
+		assert_eq!(s.parse::<SortBy>(), Ok(*sort));
+	}
+
+	assert!("size".parse::<SortBy>().is_err());
+}
+
+#[test]
+fn refinements() {
+	let cases: Vec<(Refine, &[&str])> = vec![
+		(Refine::default(), &["b", "d", "a", "c"]),
+		// sorting, the most relevant first and ties in order
+		(
+			Refine {
+				sort: Some(SortBy::Votes),
+				..Default::default()
+			},
+			&["a", "b", "c", "d"],
+		),
+		(
+			Refine {
+				sort: Some(SortBy::Popularity),
+				..Default::default()
+			},
+			&["c", "b", "a", "d"],
+		),
+		(
+			Refine {
+				sort: Some(SortBy::Modified),
+				..Default::default()
+			},
+			&["c", "b", "a", "d"],
+		),
+		(
+			Refine { sort: Some(SortBy::Name), ..Default::default() },
+			&["a", "b", "c", "d"],
+		),
+		(
+			Refine {
+				sort: Some(SortBy::Name),
+				reverse: true,
+				..Default::default()
+			},
+			&["d", "c", "b", "a"],
+		),
+		(
+			Refine { reverse: true, ..Default::default() },
+			&["c", "a", "d", "b"],
+		),
+		// filters
+		(
+			Refine { min_votes: Some(10), ..Default::default() },
+			&["b", "a", "c"],
+		),
+		(
+			Refine { min_votes: Some(0), ..Default::default() },
+			&["b", "d", "a", "c"],
+		),
+		(
+			Refine { maintained_only: true, ..Default::default() },
+			&["b", "a", "c"],
+		),
+		(
+			Refine { not_out_of_date: true, ..Default::default() },
+			&["b", "d", "c"],
+		),
+		// limits apply last
+		(
+			Refine { limit: Some(2), ..Default::default() },
+			&["b", "d"],
+		),
+		(Refine { limit: Some(0), ..Default::default() }, &[]),
+		(
+			Refine { limit: Some(9), ..Default::default() },
+			&["b", "d", "a", "c"],
+		),
+		(
+			Refine {
+				maintained_only: true,
+				not_out_of_date: true,
+				sort: Some(SortBy::Votes),
+				reverse: true,
+				limit: Some(1),
+				..Default::default()
+			},
+			&["c"],
+		),
+	];
+
+	for (refine, expected) in cases {
+		assert_eq!(names(refine.clone()), *expected, "{:?}", refine);
+	}
+}
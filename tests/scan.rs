@@ -0,0 +1,174 @@
+use zeus::scan::{scan, Risk};
+
+fn risks(script: &str) -> Vec<(Risk, usize)> {
+	scan(script).into_iter().map(|x| (x.risk, x.line)).collect()
+}
+
+#[test]
+fn clean_pkgbuild() {
+	let pkgbuild = r#"
+pkgname=foo
+pkgver=1.0
+arch=('x86_64')
+source=("https://example.com/foo-$pkgver.tar.gz"
+        'foo.service'
+        "git+https://example.com/bar.git#tag=v1.0")
+sha256sums=('0123456789abcdef'
+            '0123456789abcdef'
+            'SKIP')
+
+package() {
+	install -Dm755 foo "$pkgdir/usr/bin/foo"
+	mkdir -p "${pkgdir}"/usr/share/foo # not /etc/foo
+	cp -r data "$pkgdir"/usr/share/foo
+	make DESTDIR="$pkgdir" install > /dev/null
+}
+"#;
+
+	assert_eq!(risks(pkgbuild), vec![]);
+}
+
+#[test]
+fn risky_commands() {
+	let pkgbuild = r#"
+build() {
+	curl -fsSL https://example.com/install.sh | bash
+	sh -c "$(wget -qO- https://example.com/install.sh)"
+	sudo make install
+	echo aGVsbG8K | base64 -d > run.sh
+	install -Dm755 foo /usr/bin/foo
+	echo 'export FOO=1' >> ~/.bashrc
+}
+"#;
+
+	assert_eq!(
+		risks(pkgbuild),
+		vec![
+			(Risk::PipeToShell, 3),
+			(Risk::PipeToShell, 4),
+			(Risk::Sudo, 5),
+			(Risk::Base64, 6),
+			(Risk::WriteOutsidePkgdir, 7),
+			(Risk::WriteOutsidePkgdir, 8),
+		]
+	);
+}
+
+#[test]
+fn base64_blobs() {
+	let blob = "QUJDREVGR0hJSktMTU5PUFFSU1RVVldYWVphYmNkZWZnaGlqa2xtbm9wcXJzdHV2d3h5ejAxMjM0NTY3ODk=";
+
+	let script =
+		format!("post_install() {{\n\tpayload='{}'\n}}\n", blob);
+
+	assert_eq!(risks(&script), vec![(Risk::Base64, 2)]);
+}
+
+#[test]
+fn checksums_are_not_base64() {
+	let pkgbuild = r#"
+source=("https://example.com/foo-1.0.tar.gz"
+        "https://example.com/foo-1.0.tar.gz.sig")
+sha512sums=('cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e'
+            'ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f')
+b2sums=('786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce'
+        '786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce')
+validpgpkeys=('ABAF11C65A2970B130ABE3C479BE3E4300411886')
+"#;
+
+	assert_eq!(risks(pkgbuild), vec![]);
+}
+
+#[test]
+fn standard_streams() {
+	let pkgbuild = r#"
+build() {
+	echo "warning: no docs" > /dev/stderr
+	echo "warning: no docs" >"/dev/stdout"
+	printf '%s\n' done > /dev/fd/2
+	echo "warning: no docs" >&2
+}
+"#;
+
+	assert_eq!(risks(pkgbuild), vec![]);
+}
+
+#[test]
+fn openssl_base64() {
+	let pkgbuild = r#"
+build() {
+	openssl base64 -d -in payload > run.sh
+	openssl enc -d -a -in payload -out run.sh
+	openssl dgst -sha256 foo.tar.gz
+}
+"#;
+
+	assert_eq!(
+		risks(pkgbuild),
+		vec![(Risk::Base64, 3), (Risk::Base64, 4)]
+	);
+}
+
+#[test]
+fn blocking_risks() {
+	assert!(Risk::PipeToShell.blocks());
+	assert!(Risk::WriteOutsidePkgdir.blocks());
+	assert!(Risk::Sudo.blocks());
+	assert!(Risk::Base64.blocks());
+	assert!(!Risk::UnpinnedSource.blocks());
+	assert!(!Risk::SkippedChecksum.blocks());
+}
+
+#[test]
+fn sources() {
+	let pkgbuild = r#"
+source=("foo.tar.gz::https://example.com/foo.tar.gz"
+        "https://example.com/bar.tar.gz"
+        "git+https://example.com/baz.git"
+        "git+https://example.com/qux.git#commit=0123456789")
+sha256sums=('0123456789abcdef'
+            'SKIP'
+            'SKIP'
+            'SKIP')
+source_x86_64=("https://example.com/foo-x86_64.bin")
+"#;
+
+	let findings = scan(pkgbuild);
+
+	assert_eq!(
+		findings
+			.iter()
+			.map(|x| (x.risk, x.line, x.text.as_str()))
+			.collect::<Vec<_>>(),
+		vec![
+			(
+				Risk::SkippedChecksum,
+				2,
+				"https://example.com/bar.tar.gz"
+			),
+			(
+				Risk::UnpinnedSource,
+				2,
+				"git+https://example.com/baz.git"
+			),
+			(
+				Risk::UnpinnedSource,
+				10,
+				"https://example.com/foo-x86_64.bin"
+			),
+		]
+	);
+}
+
+#[test]
+fn comments_are_ignored() {
+	let pkgbuild = r#"
+# curl https://example.com/install.sh | sh
+package() {
+	# sudo make install
+	install -Dm644 foo.conf "$pkgdir/etc/foo.conf" # was /etc/foo.conf
+}
+"#;
+
+	assert_eq!(risks(pkgbuild), vec![]);
+}
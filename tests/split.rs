@@ -0,0 +1,156 @@
+use zeus::aur::{group_by_base, package_file_name, Package};
+
+fn package(name: &str, base: &str, depends: &[&str]) -> Package {
+	Package {
+		Name: Some(name.to_owned()),
+		PackageBase: Some(base.to_owned()),
+		Version: Some("1.0-1".to_owned()),
+		Depends: Some(
+			depends.iter().map(|x| x.to_string()).collect(),
+		),
+		..Default::default()
+	}
+}
+
+fn bases(packages: &[Package]) -> Vec<(&str, Vec<&str>)> {
+	group_by_base(packages)
+		.into_iter()
+		.map(|(base, packages)| {
+			(
+				base,
+				packages
+					.iter()
+					.filter_map(|x| x.Name.as_deref())
+					.collect(),
+			)
+		})
+		.collect()
+}
+
+#[test]
+fn package_file_names() {
+	let cases: &[(&str, Option<&str>)] = &[
+		("foo-1.0-1-x86_64.pkg.tar.zst", Some("foo")),
+		("/build/foo/foo-1.0-1-x86_64.pkg.tar.zst", Some("foo")),
+		("/build/foo-bar/foo-1.0-1-any.pkg.tar.zst", Some("foo")),
+		// epochs
+		("foo-2:1.0-1-x86_64.pkg.tar.zst", Some("foo")),
+		(
+			"foo-bar-1:1.0.r5.g1234abc-3-any.pkg.tar.xz",
+			Some("foo-bar"),
+		),
+		// names with dashes
+		("foo-bar-baz-1.0-1-x86_64.pkg.tar.zst", Some("foo-bar-baz")),
+		(
+			"python-foo-git-r12.0abc-1-any.pkg.tar.zst",
+			Some("python-foo-git"),
+		),
+		// debug packages are packages of their own
+		("foo-debug-1.0-1-x86_64.pkg.tar.zst", Some("foo-debug")),
+		(
+			"foo-bar-debug-2:1.0-1-x86_64.pkg.tar.zst",
+			Some("foo-bar-debug"),
+		),
+		// not package files
+		("foo.pkg.tar.zst", None),
+		("1.0-1-x86_64.pkg.tar.zst", None),
+	];
+
+	for (path, name) in cases {
+		assert_eq!(
+			package_file_name(path),
+			*name,
+			"name of {:?}",
+			path
+		);
+	}
+}
+
+#[test]
+fn split_packages_are_grouped() {
+	let packages = [
+		package("foo", "foo", &[]),
+		package("bar", "bar", &["foo"]),
+		package("foo-docs", "foo", &[]),
+		package("baz", "baz", &[]),
+	];
+
+	assert_eq!(
+		bases(&packages),
+		[
+			("foo", vec!["foo", "foo-docs"]),
+			("bar", vec!["bar"]),
+			("baz", vec!["baz"]),
+		]
+	);
+}
+
+#[test]
+fn packages_without_base_are_their_own() {
+	let packages = [
+		Package {
+			Name: Some("foo".to_owned()),
+			..Default::default()
+		},
+		Package::default(),
+	];
+
+	assert_eq!(bases(&packages), [("foo", vec!["foo"])]);
+}
+
+#[test]
+fn bases_come_after_their_dependencies() {
+	// the resolver puts bar before qux-docs, which needs it, but
+	// qux comes first, so the qux base has to move after bar
+	let packages = [
+		package("qux", "qux", &[]),
+		package("libbar", "bar", &[]),
+		package("qux-docs", "qux", &["libbar>=1.0"]),
+		package("zeus", "zeus", &["qux"]),
+	];
+
+	assert_eq!(
+		bases(&packages),
+		[
+			("bar", vec!["libbar"]),
+			("qux", vec!["qux", "qux-docs"]),
+			("zeus", vec!["zeus"]),
+		]
+	);
+}
+
+#[test]
+fn dependencies_between_split_packages() {
+	// packages of one base can depend on each other
+	let packages = [
+		package("foo", "foo", &[]),
+		package("foo-plugins", "foo", &["foo"]),
+		package("bar", "bar", &["foo-plugins"]),
+	];
+
+	assert_eq!(
+		bases(&packages),
+		[("foo", vec!["foo", "foo-plugins"]), ("bar", vec!["bar"])]
+	);
+}
+
+#[test]
+fn cycles_keep_their_order() {
+	// neither base can come first, so they keep the resolver's order
+	// after the bases that are ready
+	let packages = [
+		package("a", "a", &[]),
+		package("b", "b", &["a"]),
+		package("a-extra", "a", &["b"]),
+		package("c", "c", &[]),
+	];
+
+	assert_eq!(
+		bases(&packages),
+		[
+			("c", vec!["c"]),
+			("a", vec!["a", "a-extra"]),
+			("b", vec!["b"]),
+		]
+	);
+}
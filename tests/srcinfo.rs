@@ -0,0 +1,114 @@
+use zeus::aur::{AurError, SrcInfo};
+
+fn fixture() -> SrcInfo {
+	SrcInfo::parse(include_str!("fixtures/split.SRCINFO")).unwrap()
+}
+
+#[test]
+fn split_package() {
+	let srcinfo = fixture();
+
+	assert_eq!(srcinfo.base, "python-foo");
+	assert_eq!(srcinfo.version(), "1:2.1.0-3");
+	assert_eq!(
+		srcinfo.names().collect::<Vec<_>>(),
+		vec!["python-foo", "python-foo-docs"]
+	);
+
+	let packages = srcinfo.packages("x86_64");
+	assert_eq!(packages.len(), 2);
+
+	for package in &packages {
+		assert_eq!(
+			package.PackageBase.as_deref(),
+			Some("python-foo")
+		);
+		assert_eq!(package.Version.as_deref(), Some("1:2.1.0-3"));
+		assert_eq!(package.License, Some(vec!["MIT".to_owned()]));
+	}
+}
+
+#[test]
+fn overrides() {
+	let srcinfo = fixture();
+
+	let foo = srcinfo.package("python-foo", "x86_64").unwrap();
+	assert_eq!(foo.Description.as_deref(), Some("Foo bindings"));
+	assert_eq!(
+		foo.Depends,
+		Some(vec!["python".to_owned(), "glibc".to_owned()])
+	);
+	assert_eq!(
+		foo.OptDepends,
+		Some(vec!["python-numpy: array support".to_owned()])
+	);
+
+	let docs = srcinfo.package("python-foo-docs", "x86_64").unwrap();
+	assert_eq!(
+		docs.Description.as_deref(),
+		Some("Documentation for Foo bindings")
+	);
+	// cleared by the package section
+	assert_eq!(docs.Depends, None);
+	assert_eq!(docs.OptDepends, None);
+
+	assert!(srcinfo.package("python-bar", "x86_64").is_none());
+}
+
+#[test]
+fn architectures() {
+	let srcinfo = fixture();
+
+	let x86_64 = srcinfo.package("python-foo", "x86_64").unwrap();
+	assert_eq!(
+		x86_64.MakeDepends,
+		Some(vec![
+			"python-build".to_owned(),
+			"python-installer".to_owned(),
+			"nasm".to_owned(),
+		])
+	);
+	assert_eq!(
+		x86_64.CheckDepends,
+		Some(vec!["python-pytest".to_owned()])
+	);
+
+	let aarch64 = srcinfo.package("python-foo", "aarch64").unwrap();
+	assert_eq!(
+		aarch64.MakeDepends,
+		Some(vec![
+			"python-build".to_owned(),
+			"python-installer".to_owned(),
+		])
+	);
+	assert_eq!(
+		aarch64.Depends,
+		Some(vec![
+			"python".to_owned(),
+			"glibc".to_owned(),
+			"libatomic".to_owned(),
+		])
+	);
+
+	let docs = srcinfo.package("python-foo-docs", "aarch64").unwrap();
+	assert_eq!(docs.Depends, None);
+}
+
+#[test]
+fn invalid() {
+	let cases = [
+		("pkgname = foo\n", 1),
+		("pkgbase = foo\n\tpkgver 1.0\n", 2),
+		("pkgbase = foo\npkgname = foo\npkgbase = bar\n", 3),
+		("# nothing\n", 1),
+	];
+
+	for (s, line) in cases {
+		match SrcInfo::parse(s) {
+			Err(AurError::SrcInfo(l, _)) => {
+				assert_eq!(l, line, "{}", s)
+			},
+			v => panic!("{:?} parsed as {:?}", s, v),
+		}
+	}
+}
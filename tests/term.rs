@@ -0,0 +1,50 @@
+use zeus::term::parse_selection;
+
+#[test]
+fn selections() {
+	let cases: &[(&str, usize, &[usize])] = &[
+		("", 5, &[]),
+		("1", 5, &[0]),
+		("1 2 3", 5, &[0, 1, 2]),
+		("1,3", 5, &[0, 2]),
+		("3, 1", 5, &[0, 2]),
+		("2 2", 5, &[1]),
+		// ranges
+		("1-3", 5, &[0, 1, 2]),
+		("3-1", 5, &[0, 1, 2]),
+		("2-2", 5, &[1]),
+		("1-3 5", 5, &[0, 1, 2, 4]),
+		// exclusions
+		("1-3 ^2", 5, &[0, 2]),
+		("^2 1-3", 5, &[0, 2]),
+		("1 ^1", 5, &[]),
+		// only exclusions select everything else
+		("^2", 3, &[0, 2]),
+		("^1-2", 3, &[2]),
+		("^3-2", 4, &[0, 3]),
+		("^9", 3, &[0, 1, 2]),
+		// out of range numbers are ignored
+		("0", 5, &[]),
+		("6", 5, &[]),
+		("4-9", 5, &[3, 4]),
+		("9-4", 5, &[3, 4]),
+		("7-9", 5, &[]),
+		("0-2", 5, &[0, 1]),
+		// so is anything that is not a number
+		("a", 5, &[]),
+		("1-", 5, &[]),
+		("-2", 5, &[]),
+		("^", 5, &[]),
+		("1 x 3", 5, &[0, 2]),
+	];
+
+	for (input, count, expected) in cases {
+		assert_eq!(
+			parse_selection(input, *count),
+			*expected,
+			"selection {:?} of {}",
+			input,
+			count
+		);
+	}
+}
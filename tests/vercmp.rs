@@ -0,0 +1,163 @@
+use std::cmp::Ordering::{self, *};
+
+use zeus::aur::{vercmp, Version};
+
+/// Cases from pacman's `test/util/vercmptest.sh`
+const CASES: &[(&str, &str, Ordering)] = &[
+	// all similar length, no pkgrel
+	("1.5.0", "1.5.0", Equal),
+	("1.5.1", "1.5.0", Greater),
+	// mixed length
+	("1.5.1", "1.5", Greater),
+	// with pkgrel, simple
+	("1.5.0-1", "1.5.0-1", Equal),
+	("1.5.0-1", "1.5.0-2", Less),
+	("1.5.0-1", "1.5.1-1", Less),
+	("1.5.0-2", "1.5.1-1", Less),
+	// with pkgrel, mixed lengths
+	("1.5-1", "1.5.1-1", Less),
+	("1.5-2", "1.5.1-1", Less),
+	("1.5-2", "1.5.1-2", Less),
+	// mixed pkgrel inclusion
+	("1.5", "1.5-1", Equal),
+	("1.5-1", "1.5", Equal),
+	("1.1-1", "1.1", Equal),
+	("1.0-1", "1.1", Less),
+	("1.1-1", "1.0", Greater),
+	// alphanumeric versions
+	("1.5b-1", "1.5-1", Less),
+	("1.5b", "1.5", Less),
+	("1.5b-1", "1.5", Less),
+	("1.5b", "1.5.1", Less),
+	// from the manpage
+	("1.0a", "1.0alpha", Less),
+	("1.0alpha", "1.0b", Less),
+	("1.0b", "1.0beta", Less),
+	("1.0beta", "1.0rc", Less),
+	("1.0rc", "1.0", Less),
+	// alpha-dotted versions
+	("1.5.a", "1.5", Greater),
+	("1.5.b", "1.5.a", Greater),
+	("1.5.1", "1.5.b", Greater),
+	// alpha dots and dashes
+	("1.5.b-1", "1.5.b", Equal),
+	("1.5-1", "1.5.b", Less),
+	// same/similar content, differing separators
+	("2.0", "2_0", Equal),
+	("2.0_a", "2_0.a", Equal),
+	("2.0a", "2.0.a", Less),
+	("2___a", "2_a", Greater),
+	// epoch included version comparisons
+	("0:1.0", "0:1.0", Equal),
+	("0:1.0", "0:1.1", Less),
+	("1:1.0", "0:1.0", Greater),
+	("1:1.0", "0:1.1", Greater),
+	("1:1.0", "2:1.1", Less),
+	// epoch + sometimes present pkgrel
+	("1:1.0", "0:1.0-1", Greater),
+	("1:1.0-1", "0:1.1-1", Greater),
+	// epoch included on one version
+	("0:1.0", "1.0", Equal),
+	("0:1.0", "1.1", Less),
+	("0:1.1", "1.0", Greater),
+	("1:1.0", "1.0", Greater),
+	("1:1.0", "1.1", Greater),
+	("1:1.1", "1.1", Greater),
+	// leading zeros and long numbers
+	("1.010", "1.10", Equal),
+	("1.0001", "1.2", Less),
+	("20220101", "9", Greater),
+	(
+		"1.123456789012345678901",
+		"1.123456789012345678900",
+		Greater,
+	),
+	// VCS style versions
+	("r123.abcdef0-1", "r124.0123abc-1", Less),
+	("1.2.r10.g1234abc-1", "1.2.r9.gffffff0-1", Greater),
+	("1.2.r1.g1234abc-1", "1.2-1", Greater),
+	// empty components
+	("", "", Equal),
+	("", "1", Less),
+	(":1.0", "1.0", Equal),
+	("1.0-", "1.0-1", Less),
+];
+
+#[test]
+fn vercmp_table() {
+	for (a, b, expected) in CASES {
+		assert_eq!(
+			vercmp(a, b),
+			*expected,
+			"vercmp({:?}, {:?})",
+			a,
+			b
+		);
+		assert_eq!(
+			vercmp(b, a),
+			expected.reverse(),
+			"vercmp({:?}, {:?})",
+			b,
+			a
+		);
+	}
+}
+
+#[test]
+fn version_components() {
+	let cases: &[(&str, &str, &str, Option<&str>)] = &[
+		("1.0", "0", "1.0", None),
+		("1.0-1", "0", "1.0", Some("1")),
+		("2:1.0-1", "2", "1.0", Some("1")),
+		(":1.0-1", "0", "1.0", Some("1")),
+		("1.0-2-3", "0", "1.0-2", Some("3")),
+		("1a:2.0", "0", "1a:2.0", None),
+		("12", "0", "12", None),
+	];
+
+	for (full, epoch, pkgver, pkgrel) in cases {
+		let v = Version::from(*full);
+
+		assert_eq!(v.epoch(), *epoch, "epoch of {:?}", full);
+		assert_eq!(v.pkgver(), *pkgver, "pkgver of {:?}", full);
+		assert_eq!(v.pkgrel(), *pkgrel, "pkgrel of {:?}", full);
+		assert_eq!(v.to_string(), *full);
+	}
+}
+
+#[test]
+fn version_serde() {
+	let v: Version = serde_json::from_str("\"1:2.3-4\"").unwrap();
+
+	assert_eq!(v.epoch(), "1");
+	assert_eq!(serde_json::to_string(&v).unwrap(), "\"1:2.3-4\"");
+}
+
+#[test]
+fn version_order_is_total() {
+	let mut versions: Vec<Version> =
+		["1.0-2", "1.0", "0:1.0-1", "1:0.1", "1.0-1", "0.9-3"]
+			.into_iter()
+			.map(Version::from)
+			.collect();
+
+	versions.sort();
+
+	assert_eq!(
+		versions.iter().map(|x| x.as_str()).collect::<Vec<_>>(),
+		["0.9-3", "1.0", "0:1.0-1", "1.0-1", "1.0-2", "1:0.1"]
+	);
+
+	// a missing pkgrel only matches any pkgrel with vercmp
+	let (a, b, c) = (
+		Version::from("1.0-1"),
+		Version::from("1.0"),
+		Version::from("1.0-2"),
+	);
+
+	assert!(b < a && a < c);
+	assert_ne!(a, b);
+	assert_eq!(a.vercmp(&b), Equal);
+	assert_eq!(b.vercmp(&c), Equal);
+	assert_eq!(a.vercmp(&c), Less);
+}